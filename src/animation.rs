@@ -0,0 +1,164 @@
+use glam::{Mat4, Quat, Vec3};
+use rend3::Renderer;
+use rend3_gltf::{AnimationChannel, GltfSceneInstance, LoadedGltfScene};
+
+/// Name of every animation in the scene, `#index` for unnamed ones.
+pub fn clip_names(scene: &LoadedGltfScene) -> Vec<String> {
+    scene
+        .animations
+        .iter()
+        .enumerate()
+        .map(|(idx, animation)| {
+            animation
+                .label
+                .as_ref()
+                .map_or_else(|| format!("#{}", idx), |label| label.to_string())
+        })
+        .collect()
+}
+
+/// Finds an animation by name, or by index when given a number.
+pub fn find_clip(scene: &LoadedGltfScene, name: &str) -> Option<usize> {
+    clip_names(scene)
+        .iter()
+        .position(|clip| clip == name)
+        .or_else(|| {
+            name.parse()
+                .ok()
+                .filter(|&idx| idx < scene.animations.len())
+        })
+}
+
+/// Value of `channel` at `time`, interpolating linearly between keyframes.
+fn sample<T: Copy>(channel: &AnimationChannel<T>, time: f32, lerp: impl Fn(T, T, f32) -> T) -> T {
+    let next = channel.times.partition_point(|&t| t <= time);
+    if next == 0 {
+        return channel.values[0];
+    }
+    if next == channel.times.len() {
+        return channel.values[next - 1];
+    }
+    let (start, end) = (channel.times[next - 1], channel.times[next]);
+    lerp(
+        channel.values[next - 1],
+        channel.values[next],
+        (time - start) / (end - start),
+    )
+}
+
+/// Plays one animation of a scene, optionally cross-blended with a second one.
+pub struct AnimationPlayer {
+    clip: usize,
+    blend_clip: Option<usize>,
+    /// How much of `blend_clip` is mixed in, from 0 to 1.
+    pub blend: f32,
+    /// Progress through the clips from 0 to 1. Both clips share it so that cycles of different
+    /// lengths, like a walk and a run, stay in step while blending.
+    phase: f32,
+    /// Whether to start over at the end rather than stop on the last frame.
+    looping: bool,
+}
+
+impl AnimationPlayer {
+    pub fn new(clip: usize, blend_clip: Option<usize>, blend: f32, looping: bool) -> Self {
+        Self {
+            clip,
+            blend_clip,
+            blend: blend.clamp(0.0, 1.0),
+            phase: 0.0,
+            looping,
+        }
+    }
+
+    pub fn has_blend_clip(&self) -> bool {
+        self.blend_clip.is_some()
+    }
+
+    /// Moves the animation forward, looping or stopping at the end.
+    pub fn advance(&mut self, scene: &LoadedGltfScene, seconds: f32) {
+        let mut duration = scene.animations[self.clip].inner.duration;
+        if let Some(blend_clip) = self.blend_clip {
+            let blend_duration = scene.animations[blend_clip].inner.duration;
+            duration += (blend_duration - duration) * self.blend;
+        }
+        if duration > 0.0 {
+            let phase = self.phase + seconds / duration;
+            self.phase = if self.looping {
+                phase.fract()
+            } else {
+                phase.min(1.0)
+            };
+        }
+    }
+
+    /// Poses the scene's nodes, objects and skeletons at the current time.
+    pub fn pose(&self, renderer: &Renderer, scene: &LoadedGltfScene, instance: &GltfSceneInstance) {
+        profiling::scope!("pose animation");
+
+        let mut local: Vec<(Vec3, Quat, Vec3)> = instance
+            .nodes
+            .iter()
+            .map(|node| node.inner.local_transform.to_scale_rotation_translation())
+            .collect();
+        self.apply_clip(scene, self.clip, 1.0, &mut local);
+        if let Some(blend_clip) = self.blend_clip {
+            self.apply_clip(scene, blend_clip, self.blend, &mut local);
+        }
+
+        let mut global = vec![Mat4::IDENTITY; instance.nodes.len()];
+        for &idx in &instance.topological_order {
+            let (scale, rotation, translation) = local[idx];
+            let transform = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+            global[idx] = match instance.nodes[idx].inner.parent {
+                Some(parent) => global[parent] * transform,
+                None => transform,
+            };
+        }
+
+        for (idx, node) in instance.nodes.iter().enumerate() {
+            if let Some(ref object) = node.inner.object {
+                renderer.set_object_transform(&object.inner, global[idx]);
+            }
+            if let Some(ref armature) = node.inner.armature {
+                let skin = &scene.skins[armature.skin_index].inner;
+                let joints: Vec<Mat4> = skin
+                    .joints
+                    .iter()
+                    .map(|joint| global[joint.inner.node_idx])
+                    .collect();
+                for skeleton in &armature.skeletons {
+                    renderer.set_skeleton_joint_transforms(
+                        skeleton,
+                        &joints,
+                        &skin.inverse_bind_matrices,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Mixes `weight` of `clip` into the local transforms. Nodes the clip doesn't animate
+    /// are left alone.
+    fn apply_clip(
+        &self,
+        scene: &LoadedGltfScene,
+        clip: usize,
+        weight: f32,
+        local: &mut [(Vec3, Quat, Vec3)],
+    ) {
+        let animation = &scene.animations[clip].inner;
+        let time = self.phase * animation.duration;
+        for (&idx, channels) in &animation.channels {
+            let (scale, rotation, translation) = &mut local[idx];
+            if let Some(ref channel) = channels.translation {
+                *translation = translation.lerp(sample(channel, time, Vec3::lerp), weight);
+            }
+            if let Some(ref channel) = channels.rotation {
+                *rotation = rotation.slerp(sample(channel, time, Quat::slerp), weight);
+            }
+            if let Some(ref channel) = channels.scale {
+                *scale = scale.lerp(sample(channel, time, Vec3::lerp), weight);
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+
+use rend3_framework::{AssetLoader, AssetPath};
+
+/// Reported while an asset downloads.
+#[derive(Debug, Clone, Copy)]
+pub enum DownloadProgress {
+    /// A download started, with its size if the server sent one.
+    Started { total: Option<u64> },
+    /// More bytes of a started download arrived.
+    Received { bytes: u64 },
+}
+
+#[derive(Debug)]
+pub struct FetchError(String);
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Loads an asset, reporting progress as it arrives.
+///
+/// On the web this streams the response so large files can show progress. Native
+/// reads are fast enough that they report everything at once.
+pub async fn fetch(
+    loader: &AssetLoader,
+    path: AssetPath<'_>,
+    on_progress: &mut impl FnMut(DownloadProgress),
+) -> Result<Vec<u8>, FetchError> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        fetch_url(&loader.get_asset_path(path), on_progress).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let data = loader
+            .get_asset(path)
+            .await
+            .map_err(|e| FetchError(e.to_string()))?;
+        on_progress(DownloadProgress::Started {
+            total: Some(data.len() as u64),
+        });
+        on_progress(DownloadProgress::Received {
+            bytes: data.len() as u64,
+        });
+        Ok(data)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_url(
+    url: &str,
+    on_progress: &mut impl FnMut(DownloadProgress),
+) -> Result<Vec<u8>, FetchError> {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    let js_error = |e: JsValue| FetchError(format!("{}: {:?}", url, e));
+
+    let window = web_sys::window().ok_or_else(|| FetchError(format!("{}: no window", url)))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(FetchError(format!(
+            "{}: http status {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let total = response
+        .headers()
+        .get("content-length")
+        .ok()
+        .flatten()
+        .and_then(|length| length.parse().ok());
+    on_progress(DownloadProgress::Started { total });
+
+    let body = response
+        .body()
+        .ok_or_else(|| FetchError(format!("{}: response has no body", url)))?;
+    let reader: web_sys::ReadableStreamDefaultReader =
+        body.get_reader().dyn_into().map_err(js_error)?;
+
+    let mut data = Vec::with_capacity(total.unwrap_or(0) as usize);
+    loop {
+        let chunk = JsFuture::from(reader.read()).await.map_err(js_error)?;
+        let done = js_sys::Reflect::get(&chunk, &"done".into())
+            .map_err(js_error)?
+            .as_bool()
+            .unwrap_or(true);
+        if done {
+            break;
+        }
+        let value: js_sys::Uint8Array = js_sys::Reflect::get(&chunk, &"value".into())
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(js_error)?;
+
+        let start = data.len();
+        data.resize(start + value.length() as usize, 0);
+        value.copy_to(&mut data[start..]);
+        on_progress(DownloadProgress::Received {
+            bytes: value.length() as u64,
+        });
+    }
+
+    Ok(data)
+}
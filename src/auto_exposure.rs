@@ -0,0 +1,331 @@
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use glam::Vec4;
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: vec4<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+@fragment
+fn fs_luminance(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(frame, frame_sampler, in.uv).rgb;
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return vec4<f32>(log2(max(luminance, 0.0001)), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_expose(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(frame, frame_sampler, in.uv).rgb * params.x, 1.0);
+}
+";
+
+/// Width and height of the log luminance image that is read back. 64 floats are exactly the
+/// 256 bytes a row has to be aligned to for copying into a buffer.
+const LUMINANCE_SIZE: u32 = 64;
+/// Average luminance exposure aims for.
+const TARGET_LUMINANCE: f32 = 0.4;
+const MIN_EXPOSURE: f32 = 0.125;
+const MAX_EXPOSURE: f32 = 8.0;
+
+/// Adjusts exposure over time so the average brightness of the frame stays about the same.
+///
+/// rend3's tonemapping has no exposure input, so the tonemapped frame is measured and scaled
+/// instead. Measuring reads back a small log luminance image, which arrives a few frames later.
+pub struct AutoExposure {
+    luminance_pipeline: wgpu::RenderPipeline,
+    expose_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    luminance: wgpu::Texture,
+    readback: wgpu::Buffer,
+    /// Set while `readback` is mapped or waiting to be.
+    readback_pending: bool,
+    readback_ready: Arc<AtomicBool>,
+    /// Copy of the frame being measured and exposed, recreated when the frame size changes.
+    source: Option<wgpu::Texture>,
+    /// How quickly exposure moves towards its target, per second.
+    speed: f32,
+    target: f32,
+    pub exposure: f32,
+}
+
+impl AutoExposure {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, speed: f32) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("auto exposure shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("auto exposure bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("auto exposure pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, entry_point, format| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+        let luminance_pipeline = pipeline(
+            "auto exposure luminance pipeline",
+            "fs_luminance",
+            wgpu::TextureFormat::R32Float,
+        );
+        let expose_pipeline = pipeline("auto exposure pipeline", "fs_expose", format);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("auto exposure sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let luminance = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("auto exposure luminance"),
+            size: wgpu::Extent3d {
+                width: LUMINANCE_SIZE,
+                height: LUMINANCE_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("auto exposure readback"),
+            size: (LUMINANCE_SIZE * LUMINANCE_SIZE * 4) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            luminance_pipeline,
+            expose_pipeline,
+            bind_group_layout,
+            sampler,
+            luminance,
+            readback,
+            readback_pending: false,
+            readback_ready: Arc::new(AtomicBool::new(false)),
+            source: None,
+            speed,
+            target: 1.0,
+            exposure: 1.0,
+        }
+    }
+
+    /// Picks up a finished measurement and moves the exposure `seconds` further towards it.
+    pub fn update(&mut self, seconds: f32) {
+        if self.readback_ready.swap(false, Ordering::Acquire) {
+            {
+                let data = self.readback.slice(..).get_mapped_range();
+                let log_sum: f32 = data
+                    .chunks_exact(4)
+                    .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .sum();
+                // The frame was measured with the exposure of that time, undo it to get the
+                // luminance before exposure.
+                let average = (log_sum / (LUMINANCE_SIZE * LUMINANCE_SIZE) as f32).exp2();
+                self.target = (TARGET_LUMINANCE * self.exposure / average.max(0.0001))
+                    .clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+            }
+            self.readback.unmap();
+            self.readback_pending = false;
+        }
+        // Exponential smoothing, so the adaptation doesn't depend on the frame rate.
+        self.exposure += (self.target - self.exposure) * (1.0 - (-seconds * self.speed).exp());
+    }
+
+    /// Measures the frame in `frame` for a later [`AutoExposure::update`] and scales it by the
+    /// current exposure. `frame` needs to have been created with
+    /// [`wgpu::TextureUsages::COPY_SRC`].
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        frame: &wgpu::Texture,
+        target: &wgpu::TextureView,
+    ) {
+        profiling::scope!("auto exposure");
+
+        if self.source.as_ref().map(wgpu::Texture::size) != Some(frame.size()) {
+            self.source = Some(device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("auto exposure source"),
+                size: frame.size(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: frame.format(),
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            }));
+        }
+        let source = self.source.as_ref().unwrap();
+        let source_view = source.create_view(&wgpu::TextureViewDescriptor::default());
+        let luminance_view = self
+            .luminance
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("auto exposure params"),
+            contents: &Vec4::new(self.exposure, 0.0, 0.0, 0.0)
+                .to_array()
+                .map(f32::to_ne_bytes)
+                .concat(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("auto exposure bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("auto exposure encoder"),
+        });
+        encoder.copy_texture_to_texture(
+            frame.as_image_copy(),
+            source.as_image_copy(),
+            frame.size(),
+        );
+        let pending = self.readback_pending;
+        let passes = [
+            (&luminance_view, &self.luminance_pipeline, !pending),
+            (target, &self.expose_pipeline, true),
+        ];
+        for (view, pipeline, enabled) in passes {
+            if !enabled {
+                continue;
+            }
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("auto exposure pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        if !pending {
+            encoder.copy_texture_to_buffer(
+                self.luminance.as_image_copy(),
+                wgpu::ImageCopyBuffer {
+                    buffer: &self.readback,
+                    layout: wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(LUMINANCE_SIZE * 4),
+                        rows_per_image: None,
+                    },
+                },
+                self.luminance.size(),
+            );
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        if !pending {
+            self.readback_pending = true;
+            let ready = Arc::clone(&self.readback_ready);
+            self.readback
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    if result.is_ok() {
+                        ready.store(true, Ordering::Release);
+                    }
+                });
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use glam::{Mat4, Vec3};
+use rend3::{
+    types::{Object, ObjectHandle, ObjectMeshKind},
+    Renderer,
+};
+use rend3_routine::pbr::{AlbedoComponent, PbrMaterial};
+
+use crate::shapes::ShapeBuilder;
+
+/// Distance between the centers of neighbouring cubes.
+const SPACING: f32 = 2.0;
+
+const COLORS: [Vec3; 4] = [
+    Vec3::new(0.8, 0.2, 0.2),
+    Vec3::new(0.2, 0.8, 0.2),
+    Vec3::new(0.2, 0.2, 0.8),
+    Vec3::new(0.8, 0.8, 0.8),
+];
+
+/// Spawns `count` unit cubes in a cube shaped grid centered on the origin.
+///
+/// The layout only depends on `count`, so runs with the same count are comparable.
+pub fn spawn_grid(renderer: &Renderer, count: u32) -> anyhow::Result<Vec<ObjectHandle>> {
+    let mesh = renderer.add_mesh(
+        ShapeBuilder::new()
+            .cuboid(Vec3::splat(-0.5), Vec3::splat(0.5))
+            .build(renderer.handedness)?,
+    )?;
+    let materials: Vec<_> = COLORS
+        .iter()
+        .map(|color| {
+            renderer.add_material(PbrMaterial {
+                albedo: AlbedoComponent::Value(color.extend(1.0)),
+                ..PbrMaterial::default()
+            })
+        })
+        .collect();
+
+    let side = (count as f32).cbrt().ceil().max(1.0) as u32;
+    let offset = Vec3::splat((side - 1) as f32 * SPACING / 2.0);
+
+    Ok((0..count)
+        .map(|idx| {
+            let cell = Vec3::new(
+                (idx % side) as f32,
+                (idx / side % side) as f32,
+                (idx / (side * side)) as f32,
+            );
+            renderer.add_object(Object {
+                mesh_kind: ObjectMeshKind::Static(mesh.clone()),
+                material: materials[idx as usize % materials.len()].clone(),
+                transform: Mat4::from_translation(cell * SPACING - offset),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,75 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use glam::{Mat4, Vec3A};
+use web_time::Instant;
+
+/// Per frame camera state as csv, for matching external renders to the viewer's output.
+///
+/// Matrices are written column major.
+pub struct CameraLog {
+    writer: BufWriter<File>,
+    frame: u64,
+    start: Instant,
+}
+
+fn matrix_header(out: &mut impl Write, name: &str) -> io::Result<()> {
+    for column in 0..4 {
+        for row in 0..4 {
+            write!(out, ",{}{}{}", name, column, row)?;
+        }
+    }
+    Ok(())
+}
+
+impl CameraLog {
+    pub fn create(path: &str) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "frame,seconds,x,y,z,pitch,yaw,vfov")?;
+        matrix_header(&mut writer, "view")?;
+        matrix_header(&mut writer, "projection")?;
+        writeln!(writer)?;
+
+        Ok(Self {
+            writer,
+            frame: 0,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn write(
+        &mut self,
+        location: Vec3A,
+        pitch: f32,
+        yaw: f32,
+        vfov: f32,
+        view: &Mat4,
+        projection: &Mat4,
+    ) -> io::Result<()> {
+        write!(
+            self.writer,
+            "{},{},{},{},{},{},{},{}",
+            self.frame,
+            self.start.elapsed().as_secs_f64(),
+            location.x,
+            location.y,
+            location.z,
+            pitch,
+            yaw,
+            vfov
+        )?;
+        for value in view
+            .to_cols_array()
+            .iter()
+            .chain(&projection.to_cols_array())
+        {
+            write!(self.writer, ",{}", value)?;
+        }
+        writeln!(self.writer)?;
+        self.frame += 1;
+
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    io::BufRead,
+    path::PathBuf,
+    sync::mpsc::{self, TryRecvError},
+};
+
+/// Camera views read line by line from stdin, each rendered and saved as a png before the next
+/// one is read.
+///
+/// A line is a camera like --camera, `x,y,z,pitch,yaw`, optionally followed by whitespace and
+/// the path to save the frame to. Without a path frames are numbered in the output directory.
+pub struct CameraScript {
+    lines: mpsc::Receiver<String>,
+    output_dir: PathBuf,
+    frame: u32,
+}
+
+pub enum ScriptStep {
+    /// Render this camera, `[x, y, z, pitch, yaw]`, and save the frame to the path.
+    Camera([f32; 5], PathBuf),
+    /// Nothing new on stdin yet.
+    Waiting,
+    /// A line that isn't a camera, with what is wrong with it.
+    Invalid(String),
+    /// Stdin was closed.
+    Finished,
+}
+
+impl CameraScript {
+    /// Starts reading stdin on a thread of its own, so waiting for lines doesn't block rendering.
+    pub fn spawn(output_dir: PathBuf) -> Self {
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            lines,
+            output_dir,
+            frame: 0,
+        }
+    }
+
+    pub fn next(&mut self) -> ScriptStep {
+        let line = match self.lines.try_recv() {
+            Ok(line) => line,
+            Err(TryRecvError::Empty) => return ScriptStep::Waiting,
+            Err(TryRecvError::Disconnected) => return ScriptStep::Finished,
+        };
+        let mut parts = line.split_whitespace();
+        let Some(camera) = parts.next() else {
+            // Blank lines are skipped.
+            return ScriptStep::Waiting;
+        };
+        let camera = match crate::extract_array(camera, [0.0; 5]) {
+            Ok(camera) => camera,
+            Err(e) => return ScriptStep::Invalid(format!("{}: {}", line, e)),
+        };
+        let path = match parts.next() {
+            Some(path) => PathBuf::from(path),
+            None => self.output_dir.join(format!("frame-{:04}.png", self.frame)),
+        };
+        self.frame += 1;
+        ScriptStep::Camera(camera, path)
+    }
+}
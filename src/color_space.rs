@@ -0,0 +1,111 @@
+use std::collections::{BTreeSet, HashMap};
+
+use glam::UVec2;
+use rend3::{
+    types::{MipmapCount, MipmapSource, Texture, TextureFormat, TextureHandle},
+    Renderer,
+};
+
+/// Color space the base color and emissive textures of a gltf are decoded in.
+///
+/// rend3-gltf follows the gltf spec: color textures are uploaded as sRGB and data textures, the
+/// normal, metallic-roughness and occlusion maps, as linear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
+    /// For files with colors authored as linear values, which look washed out as sRGB.
+    Linear,
+}
+
+fn color_images(document: &gltf::Document) -> BTreeSet<usize> {
+    document
+        .materials()
+        .flat_map(|material| {
+            [
+                material
+                    .pbr_metallic_roughness()
+                    .base_color_texture()
+                    .map(|info| info.texture()),
+                material.emissive_texture().map(|info| info.texture()),
+            ]
+        })
+        .flatten()
+        .map(|texture| texture.source().index())
+        .collect()
+}
+
+fn data_images(document: &gltf::Document) -> BTreeSet<usize> {
+    document
+        .materials()
+        .flat_map(|material| {
+            [
+                material
+                    .pbr_metallic_roughness()
+                    .metallic_roughness_texture()
+                    .map(|info| info.texture()),
+                material.normal_texture().map(|info| info.texture()),
+                material.occlusion_texture().map(|info| info.texture()),
+            ]
+        })
+        .flatten()
+        .map(|texture| texture.source().index())
+        .collect()
+}
+
+/// Images used by both color and data textures. One of the uses decodes them wrong, which is
+/// usually a mistake in the file.
+pub fn mixed_images(document: &gltf::Document) -> Vec<usize> {
+    color_images(document)
+        .intersection(&data_images(document))
+        .copied()
+        .collect()
+}
+
+/// Decodes the images of the base color and emissive textures again and uploads them as linear
+/// data, by image index. `uri_images` has the images that aren't in a buffer, by uri. Images
+/// that fail to decode are left out, keeping rend3-gltf's sRGB texture.
+pub fn upload_linear(
+    renderer: &Renderer,
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    uri_images: &HashMap<String, Vec<u8>>,
+) -> HashMap<usize, TextureHandle> {
+    profiling::scope!("upload linear color textures");
+
+    let indices = color_images(document);
+    document
+        .images()
+        .filter(|image| indices.contains(&image.index()))
+        .filter_map(|image| {
+            let data = match image.source() {
+                gltf::image::Source::View { view, .. } => buffers
+                    .get(view.buffer().index())?
+                    .get(view.offset()..view.offset() + view.length())?,
+                gltf::image::Source::Uri { uri, .. } => uri_images.get(uri)?,
+            };
+            let decoded = match image::load_from_memory(data) {
+                Ok(decoded) => decoded.into_rgba8(),
+                Err(e) => {
+                    log::warn!("Failed to decode image {} as linear: {}", image.index(), e);
+                    return None;
+                }
+            };
+            let size = UVec2::new(decoded.width(), decoded.height());
+            let texture = renderer.add_texture_2d(Texture {
+                label: image.name().map(str::to_owned),
+                data: decoded.into_raw(),
+                format: TextureFormat::Rgba8Unorm,
+                size,
+                mip_count: MipmapCount::Maximum,
+                mip_source: MipmapSource::Generated,
+            });
+            match texture {
+                Ok(texture) => Some((image.index(), texture)),
+                Err(e) => {
+                    log::warn!("Failed to upload image {} as linear: {}", image.index(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
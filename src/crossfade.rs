@@ -0,0 +1,249 @@
+use std::{borrow::Cow, time::Duration};
+
+use glam::Vec4;
+use web_time::Instant;
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: vec4<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(frame, frame_sampler, in.uv).rgb, params.x);
+}
+";
+
+/// Fades from a held copy of an earlier frame to whatever is rendered now.
+///
+/// A frame is captured when loading starts and drawn fully opaque over the
+/// live frame until [`CrossFade::start`] is called, then it fades out over
+/// the configured duration.
+pub struct CrossFade {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    duration: Duration,
+    capture_requested: bool,
+    held: Option<wgpu::Texture>,
+    fade_start: Option<Instant>,
+}
+
+impl CrossFade {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, duration: Duration) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("crossfade shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("crossfade bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("crossfade pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("crossfade pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("crossfade sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            duration,
+            capture_requested: false,
+            held: None,
+            fade_start: None,
+        }
+    }
+
+    /// Holds on to the next frame passed to [`CrossFade::capture`].
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+
+    /// Starts fading out the held frame, if there is one.
+    pub fn start(&mut self) {
+        self.capture_requested = false;
+        if self.held.is_some() {
+            self.fade_start = Some(Instant::now());
+        }
+    }
+
+    /// Copies `frame` into the held texture if a capture was requested. `frame` needs to
+    /// have been created with [`wgpu::TextureUsages::COPY_SRC`].
+    pub fn capture(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, frame: &wgpu::Texture) {
+        if !self.capture_requested {
+            return;
+        }
+        self.capture_requested = false;
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("crossfade held frame"),
+            size: frame.size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: frame.format(),
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("crossfade capture encoder"),
+        });
+        encoder.copy_texture_to_texture(
+            frame.as_image_copy(),
+            texture.as_image_copy(),
+            frame.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        self.held = Some(texture);
+        self.fade_start = None;
+    }
+
+    /// Draws the held frame over `target`, opaque until the fade starts.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+    ) {
+        profiling::scope!("crossfade");
+
+        let Some(ref held) = self.held else {
+            return;
+        };
+        let alpha = match self.fade_start {
+            Some(_) if self.duration.is_zero() => 0.0,
+            Some(start) => 1.0 - start.elapsed().as_secs_f32() / self.duration.as_secs_f32(),
+            None => 1.0,
+        };
+        if alpha <= 0.0 {
+            self.held = None;
+            self.fade_start = None;
+            return;
+        }
+
+        let view = held.create_view(&wgpu::TextureViewDescriptor::default());
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("crossfade params"),
+            contents: &Vec4::new(alpha, 0.0, 0.0, 0.0)
+                .to_array()
+                .map(f32::to_ne_bytes)
+                .concat(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("crossfade bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("crossfade encoder"),
+        });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("crossfade pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(&self.pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
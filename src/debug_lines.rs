@@ -0,0 +1,311 @@
+use std::borrow::Cow;
+
+use glam::{Mat4, UVec2, Vec3, Vec4};
+use wgpu::util::DeviceExt;
+
+/// Expands each instance of a segment buffer, see [`segment_buffer_layout`], into a quad
+/// facing the camera that fades out over its edges. Shared with the wireframe.
+pub const SHADER: &str = "
+struct Params {
+    view_proj: mat4x4<f32>,
+    viewport: vec2<f32>,
+    half_width: f32,
+    _padding: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    // Pixels from the middle of the line.
+    @location(1) @interpolate(linear) across: f32,
+};
+
+// Segments are clipped this far in front of the camera, dividing by w flips points behind it.
+const NEAR_W: f32 = 0.0001;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) index: u32,
+    @location(0) start: vec3<f32>,
+    @location(1) end: vec3<f32>,
+    @location(2) color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = color;
+    out.across = 0.0;
+    var a = params.view_proj * vec4<f32>(start, 1.0);
+    var b = params.view_proj * vec4<f32>(end, 1.0);
+    if a.w < NEAR_W && b.w < NEAR_W {
+        // Entirely behind the camera, every corner in the same spot draws nothing.
+        out.position = vec4<f32>(2.0, 2.0, 0.5, 1.0);
+        return out;
+    }
+    if a.w < NEAR_W {
+        a = mix(a, b, (NEAR_W - a.w) / (b.w - a.w));
+    }
+    if b.w < NEAR_W {
+        b = mix(b, a, (NEAR_W - b.w) / (a.w - b.w));
+    }
+
+    let a_pixels = a.xy / a.w * params.viewport * 0.5;
+    let b_pixels = b.xy / b.w * params.viewport * 0.5;
+    var along = b_pixels - a_pixels;
+    if length(along) < 0.0001 {
+        along = vec2<f32>(1.0, 0.0);
+    }
+    along = normalize(along);
+    let normal = vec2<f32>(-along.y, along.x);
+
+    // Two triangles per segment, corner bits pick the end and the side of each vertex.
+    let corner = index % 6u;
+    let at_end = f32((0x32u >> corner) & 1u);
+    let side = f32((0x2cu >> corner) & 1u) * 2.0 - 1.0;
+    // A pixel more than the width for the edges to fade out in.
+    let extent = params.half_width + 1.0;
+    let offset = (normal * side + along * (at_end * 2.0 - 1.0)) * extent;
+    let clip = mix(a, b, at_end);
+    out.position = clip + vec4<f32>(offset / params.viewport * 2.0 * clip.w, 0.0, 0.0);
+    out.across = side * extent;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = clamp(params.half_width + 0.5 - abs(in.across), 0.0, 1.0);
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
+";
+
+/// Start, end and color.
+pub const SEGMENT_SIZE: u64 = (3 + 3 + 4) * 4;
+const SEGMENT_ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+    0 => Float32x3,
+    1 => Float32x3,
+    2 => Float32x4,
+];
+
+/// Vertex buffer with a start, end and color per instance, one instance per segment.
+pub fn segment_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+    wgpu::VertexBufferLayout {
+        array_stride: SEGMENT_SIZE,
+        step_mode: wgpu::VertexStepMode::Instance,
+        attributes: &SEGMENT_ATTRIBUTES,
+    }
+}
+
+/// Segments as the bytes of a segment buffer.
+pub fn segment_bytes(segments: impl IntoIterator<Item = (Vec3, Vec3, Vec4)>) -> Vec<u8> {
+    segments
+        .into_iter()
+        .flat_map(|(start, end, color)| {
+            start
+                .to_array()
+                .into_iter()
+                .chain(end.to_array())
+                .chain(color.to_array())
+        })
+        .flat_map(f32::to_ne_bytes)
+        .collect()
+}
+
+/// Contents of the shader's uniform buffer for a viewport of `size` pixels.
+pub fn params_bytes(view_proj: Mat4, size: UVec2, width: f32) -> Vec<u8> {
+    let size = size.max(UVec2::ONE).as_vec2();
+    view_proj
+        .to_cols_array()
+        .into_iter()
+        .chain([size.x, size.y, width * 0.5, 0.0])
+        .flat_map(f32::to_ne_bytes)
+        .collect()
+}
+
+/// Anti-aliased lines for debug visualizations, batched into a single draw.
+///
+/// Segments are added each frame with [`DebugLines::line`] and stay until
+/// [`DebugLines::clear`], so every view of the frame draws the same ones. They are drawn over
+/// the frame without depth testing, as rend3 doesn't share its depth buffer.
+pub struct DebugLines {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    segments: Vec<(Vec3, Vec3, Vec4)>,
+    /// Segment buffer and how many segments it fits, grown as needed.
+    buffer: Option<(wgpu::Buffer, usize)>,
+    /// Set when `segments` changed since they were last uploaded.
+    dirty: bool,
+    /// In pixels.
+    width: f32,
+}
+
+impl DebugLines {
+    /// Lines are `width` pixels wide.
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, width: f32) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("debug lines shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("debug lines bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("debug lines pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("debug lines pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[segment_buffer_layout()],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            segments: Vec::new(),
+            buffer: None,
+            dirty: false,
+            width,
+        }
+    }
+
+    /// Removes all segments, to build the next frame's.
+    pub fn clear(&mut self) {
+        if !self.segments.is_empty() {
+            self.segments.clear();
+            self.dirty = true;
+        }
+    }
+
+    /// Adds a world space segment. The alpha of `color` blends it with the frame.
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec4) {
+        self.segments.push((start, end, color));
+        self.dirty = true;
+    }
+
+    /// Adds the edges of the cube from -1 to 1 on every axis, placed with `transform`.
+    pub fn cuboid(&mut self, transform: Mat4, color: Vec4) {
+        let corner = |idx: u32| {
+            let unit = Vec3::new(
+                if idx & 1 == 0 { -1.0 } else { 1.0 },
+                if idx & 2 == 0 { -1.0 } else { 1.0 },
+                if idx & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            transform.transform_point3(unit)
+        };
+        // Every pair of corners one bit apart is an edge.
+        for idx in 0..8 {
+            for bit in [1, 2, 4] {
+                if idx & bit == 0 {
+                    self.line(corner(idx), corner(idx | bit), color);
+                }
+            }
+        }
+    }
+
+    /// Draws the segments into the `viewport` part of `target`.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        viewport: rend3::graph::ViewportRect,
+        view_proj: Mat4,
+    ) {
+        profiling::scope!("debug lines");
+
+        if self.segments.is_empty() {
+            return;
+        }
+        if self.dirty {
+            if self.buffer.as_ref().map_or(0, |(_, capacity)| *capacity) < self.segments.len() {
+                let capacity = self.segments.len().next_power_of_two();
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("debug line segments"),
+                    size: capacity as u64 * SEGMENT_SIZE,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                self.buffer = Some((buffer, capacity));
+            }
+            let (ref buffer, _) = self.buffer.as_ref().unwrap();
+            queue.write_buffer(buffer, 0, &segment_bytes(self.segments.iter().copied()));
+            self.dirty = false;
+        }
+        let (ref buffer, _) = self.buffer.as_ref().unwrap();
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("debug lines params"),
+            contents: &params_bytes(view_proj, viewport.size, self.width),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("debug lines bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: params.as_entire_binding(),
+            }],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("debug lines encoder"),
+        });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("debug lines pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_viewport(
+                viewport.offset.x as f32,
+                viewport.offset.y as f32,
+                viewport.size.x as f32,
+                viewport.size.y as f32,
+                0.0,
+                1.0,
+            );
+            rpass.set_pipeline(&self.pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.set_vertex_buffer(0, buffer.slice(..self.segments.len() as u64 * SEGMENT_SIZE));
+            rpass.draw(0..6, 0..self.segments.len() as u32);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
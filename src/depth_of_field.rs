@@ -0,0 +1,525 @@
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use glam::{Mat4, UVec2, Vec3, Vec4};
+use wgpu::util::DeviceExt;
+
+const DEPTH_SHADER: &str = "
+@group(0) @binding(0) var<uniform> view_proj: mat4x4<f32>;
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return view_proj * vec4<f32>(position, 1.0);
+}
+";
+
+const BLUR_SHADER: &str = "
+struct Params {
+    inv_projection: mat4x4<f32>,
+    // Offset and size of the view in the frame, in pixels.
+    viewport: vec4<f32>,
+    focus: f32,
+    // Blur radius in pixels for something infinitely far behind the focus.
+    blur_scale: f32,
+    max_radius: f32,
+    _padding: f32,
+};
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var depth: texture_depth_2d;
+@group(0) @binding(2) var<uniform> params: Params;
+
+const SAMPLES: u32 = 48u;
+const GOLDEN_ANGLE: f32 = 2.39996323;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn distance_at(pixel: vec2<i32>) -> f32 {
+    let d = textureLoad(depth, pixel, 0);
+    // Reverse z, nothing was drawn here.
+    if d <= 0.0 {
+        return 1e30;
+    }
+    let uv = (vec2<f32>(pixel) + 0.5 - params.viewport.xy) / params.viewport.zw;
+    let view = params.inv_projection * vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, d, 1.0);
+    // Either sign, depending on handedness.
+    return abs(view.z / view.w);
+}
+
+fn blur_radius(distance: f32) -> f32 {
+    return min(params.blur_scale * abs(distance - params.focus) / distance, params.max_radius);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let center = vec2<i32>(position.xy);
+    let low = vec2<i32>(params.viewport.xy);
+    let high = low + vec2<i32>(params.viewport.zw) - 1;
+    let radius = blur_radius(distance_at(center));
+
+    var color = textureLoad(frame, center, 0).rgb;
+    var weight = 1.0;
+    // Spiral of samples spread evenly over the blur disk.
+    for (var i = 0u; i < SAMPLES; i++) {
+        let r = sqrt((f32(i) + 0.5) / f32(SAMPLES)) * radius;
+        let theta = f32(i) * GOLDEN_ANGLE;
+        let pixel = clamp(center + vec2<i32>(round(vec2<f32>(cos(theta), sin(theta)) * r)), low, high);
+        // Only samples that are blurry enough to reach this pixel count, so sharp things in
+        // focus don't bleed into the blurred surroundings.
+        let sample_weight = clamp(blur_radius(distance_at(pixel)) - r + 1.0, 0.0, 1.0);
+        color += textureLoad(frame, pixel, 0).rgb * sample_weight;
+        weight += sample_weight;
+    }
+    return vec4<f32>(color / weight, 1.0);
+}
+";
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+/// Blur radius is capped at this many pixels to bound the cost and the sample spacing.
+const MAX_RADIUS: f32 = 24.0;
+/// How quickly autofocus moves to a new distance, per second.
+const AUTOFOCUS_SPEED: f32 = 4.0;
+
+/// Blurs what is out of focus.
+///
+/// rend3's base render graph keeps its depth buffer to itself and tonemaps inside of it, so the
+/// scene's triangles are drawn into a depth buffer of their own, in their rest pose, and the
+/// tonemapped frame is blurred by it.
+pub struct DepthOfField {
+    depth_pipeline: wgpu::RenderPipeline,
+    blur_pipeline: wgpu::RenderPipeline,
+    depth_bind_group_layout: wgpu::BindGroupLayout,
+    blur_bind_group_layout: wgpu::BindGroupLayout,
+    meshes: Vec<(wgpu::Buffer, wgpu::Buffer, u32)>,
+    depth: Option<(UVec2, wgpu::Texture)>,
+    /// Copy of the frame being blurred, recreated when the frame size changes.
+    source: Option<wgpu::Texture>,
+    /// Distance from the camera that is in focus.
+    pub focus: f32,
+    /// Blur radius in percent of the view height for something infinitely far behind the focus.
+    aperture: f32,
+    autofocus: Option<Autofocus>,
+}
+
+/// Reads back the depth in the middle of the view to focus on.
+struct Autofocus {
+    readback: wgpu::Buffer,
+    /// Projection the pending readback was rendered with, to turn its depth into a distance.
+    pending: Option<Mat4>,
+    ready: Arc<AtomicBool>,
+    target: f32,
+}
+
+impl DepthOfField {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        focus: f32,
+        aperture: f32,
+        autofocus: bool,
+    ) -> Self {
+        let depth_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("depth of field depth shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(DEPTH_SHADER)),
+        });
+        let blur_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("depth of field blur shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(BLUR_SHADER)),
+        });
+        let uniform_entry = |binding, visibility| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let depth_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("depth of field depth bind group layout"),
+                entries: &[uniform_entry(0, wgpu::ShaderStages::VERTEX)],
+            });
+        let blur_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("depth of field blur bind group layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Depth,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    uniform_entry(2, wgpu::ShaderStages::FRAGMENT),
+                ],
+            });
+
+        let depth_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("depth of field depth pipeline layout"),
+            bind_group_layouts: &[&depth_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let depth_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("depth of field depth pipeline"),
+            layout: Some(&depth_layout),
+            vertex: wgpu::VertexState {
+                module: &depth_module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Vec3>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            // rend3 uses reverse z, so nearer is greater.
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::GreaterEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: None,
+            multiview: None,
+        });
+
+        let blur_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("depth of field blur pipeline layout"),
+            bind_group_layouts: &[&blur_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let blur_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("depth of field blur pipeline"),
+            layout: Some(&blur_layout),
+            vertex: wgpu::VertexState {
+                module: &blur_module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &blur_module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        let autofocus = autofocus.then(|| Autofocus {
+            readback: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("depth of field autofocus readback"),
+                size: 4,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            pending: None,
+            ready: Arc::new(AtomicBool::new(false)),
+            target: focus,
+        });
+
+        Self {
+            depth_pipeline,
+            blur_pipeline,
+            depth_bind_group_layout,
+            blur_bind_group_layout,
+            meshes: Vec::new(),
+            depth: None,
+            source: None,
+            focus,
+            aperture,
+            autofocus,
+        }
+    }
+
+    /// Removes every mesh added so far.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+
+    /// Adds triangles that block the view, see [`crate::wireframe::collect_triangles`].
+    pub fn add_mesh(&mut self, device: &wgpu::Device, positions: &[Vec3], indices: &[u32]) {
+        if indices.is_empty() {
+            return;
+        }
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("depth of field vertices"),
+            contents: &positions
+                .iter()
+                .flat_map(|p| p.to_array())
+                .flat_map(f32::to_ne_bytes)
+                .collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("depth of field indices"),
+            contents: &indices
+                .iter()
+                .flat_map(|idx| idx.to_ne_bytes())
+                .collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        self.meshes
+            .push((vertex_buffer, index_buffer, indices.len() as u32));
+    }
+
+    /// Picks up a finished autofocus measurement and moves the focus `seconds` further towards it.
+    pub fn update(&mut self, seconds: f32) {
+        let Some(ref mut autofocus) = self.autofocus else {
+            return;
+        };
+        if autofocus.ready.swap(false, Ordering::Acquire) {
+            {
+                let data = autofocus.readback.slice(..).get_mapped_range();
+                let depth = f32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+                let projection = autofocus.pending.unwrap();
+                // Looking at nothing keeps the previous focus.
+                if depth > 0.0 {
+                    let view = projection.inverse() * Vec4::new(0.0, 0.0, depth, 1.0);
+                    autofocus.target = (view.z / view.w).abs();
+                }
+            }
+            autofocus.readback.unmap();
+            autofocus.pending = None;
+        }
+        self.focus += (autofocus.target - self.focus) * (1.0 - (-seconds * AUTOFOCUS_SPEED).exp());
+    }
+
+    /// Blurs the `viewport` part of the frame, which is `resolution` big. The view is rendered
+    /// with `projection` and `view_proj`. With `measure_focus`, the middle of this view is what
+    /// autofocus focuses on.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        frame: &wgpu::Texture,
+        target: &wgpu::TextureView,
+        viewport: rend3::graph::ViewportRect,
+        projection: Mat4,
+        view_proj: Mat4,
+        measure_focus: bool,
+    ) {
+        profiling::scope!("depth of field");
+
+        if self.meshes.is_empty() {
+            return;
+        }
+        let resolution = UVec2::new(frame.width(), frame.height());
+        if self.depth.as_ref().map(|(size, _)| *size) != Some(resolution) {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("depth of field depth"),
+                size: frame.size(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            self.depth = Some((resolution, texture));
+            self.source = Some(device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("depth of field source"),
+                size: frame.size(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: frame.format(),
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            }));
+        }
+        let (_, ref depth) = self.depth.as_ref().unwrap();
+        let source = self.source.as_ref().unwrap();
+        let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+        let source_view = source.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let view_proj_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("depth of field view projection"),
+            contents: &view_proj.to_cols_array().map(f32::to_ne_bytes).concat(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let depth_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth of field depth bind group"),
+            layout: &self.depth_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: view_proj_buffer.as_entire_binding(),
+            }],
+        });
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("depth of field params"),
+            contents: &projection
+                .inverse()
+                .to_cols_array()
+                .into_iter()
+                .chain([
+                    viewport.offset.x as f32,
+                    viewport.offset.y as f32,
+                    viewport.size.x as f32,
+                    viewport.size.y as f32,
+                    self.focus,
+                    self.aperture / 100.0 * viewport.size.y as f32,
+                    MAX_RADIUS,
+                    0.0,
+                ])
+                .flat_map(f32::to_ne_bytes)
+                .collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let blur_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth of field blur bind group"),
+            layout: &self.blur_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("depth of field encoder"),
+        });
+        encoder.copy_texture_to_texture(
+            frame.as_image_copy(),
+            source.as_image_copy(),
+            frame.size(),
+        );
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("depth of field depth pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(0.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            set_viewport(&mut rpass, viewport);
+            rpass.set_pipeline(&self.depth_pipeline);
+            rpass.set_bind_group(0, &depth_bind_group, &[]);
+            for (vertex_buffer, index_buffer, index_count) in &self.meshes {
+                rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                rpass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                rpass.draw_indexed(0..*index_count, 0, 0..1);
+            }
+        }
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("depth of field blur pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            set_viewport(&mut rpass, viewport);
+            rpass.set_pipeline(&self.blur_pipeline);
+            rpass.set_bind_group(0, &blur_bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        let autofocus = self
+            .autofocus
+            .as_mut()
+            .filter(|autofocus| measure_focus && autofocus.pending.is_none());
+        if let Some(ref autofocus) = autofocus {
+            let center = viewport.offset + viewport.size / 2;
+            encoder.copy_texture_to_buffer(
+                wgpu::ImageCopyTexture {
+                    texture: depth,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: center.x,
+                        y: center.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::DepthOnly,
+                },
+                wgpu::ImageCopyBuffer {
+                    buffer: &autofocus.readback,
+                    layout: wgpu::ImageDataLayout::default(),
+                },
+                wgpu::Extent3d::default(),
+            );
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        if let Some(autofocus) = autofocus {
+            autofocus.pending = Some(projection);
+            let ready = Arc::clone(&autofocus.ready);
+            autofocus
+                .readback
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    if result.is_ok() {
+                        ready.store(true, Ordering::Release);
+                    }
+                });
+        }
+    }
+}
+
+fn set_viewport(rpass: &mut wgpu::RenderPass<'_>, viewport: rend3::graph::ViewportRect) {
+    rpass.set_viewport(
+        viewport.offset.x as f32,
+        viewport.offset.y as f32,
+        viewport.size.x as f32,
+        viewport.size.y as f32,
+        0.0,
+        1.0,
+    );
+}
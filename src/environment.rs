@@ -0,0 +1,126 @@
+use glam::Vec3;
+use image::DynamicImage;
+
+/// Latitude of the center of row `y` of an equirectangular image, in radians.
+fn row_latitude(y: u32, height: u32) -> f32 {
+    (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI
+}
+
+/// Average radiance of an equirectangular environment over the whole sphere.
+///
+/// Rows are weighted by the solid angle they cover, so the stretched poles don't
+/// dominate the result.
+pub fn average_radiance(image: &DynamicImage) -> Vec3 {
+    let image = image.to_rgb32f();
+    let (width, height) = image.dimensions();
+
+    let mut sum = Vec3::ZERO;
+    let mut weight_sum = 0.0;
+    for y in 0..height {
+        let weight = row_latitude(y, height).cos();
+        let row_sum: Vec3 = (0..width)
+            .map(|x| Vec3::from(image.get_pixel(x, y).0))
+            .sum();
+        sum += row_sum * weight;
+        weight_sum += weight * width as f32;
+    }
+
+    if weight_sum > 0.0 {
+        sum / weight_sum
+    } else {
+        Vec3::ZERO
+    }
+}
+
+/// Direction towards the center of pixel `(x, y)` of an equirectangular image. The middle of
+/// the image faces -Z and a quarter further to the right faces +X.
+fn pixel_direction(x: f32, y: u32, width: u32, height: u32) -> Vec3 {
+    let longitude = ((x + 0.5) / width as f32 - 0.5) * std::f32::consts::TAU;
+    let latitude = row_latitude(y, height);
+    Vec3::new(
+        longitude.sin() * latitude.cos(),
+        latitude.sin(),
+        -longitude.cos() * latitude.cos(),
+    )
+}
+
+/// A directional light standing in for the brightest spot of an environment, like the sun.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyLight {
+    /// Direction the light travels in, away from the bright spot.
+    pub direction: Vec3,
+    /// Color with the brightest channel at 1.
+    pub color: Vec3,
+    pub intensity: f32,
+    /// Light arriving from the spot, radiance integrated over its solid angle.
+    pub irradiance: Vec3,
+}
+
+/// Width of the grid the environment is averaged down to before looking for the brightest spot,
+/// so a single hot pixel doesn't count as one.
+const KEY_LIGHT_GRID_WIDTH: u32 = 128;
+/// Cells at least this fraction of the brightest cell's luminance belong to the bright spot.
+const KEY_LIGHT_THRESHOLD: f32 = 0.5;
+
+fn luminance(color: Vec3) -> f32 {
+    color.dot(Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+/// Finds the brightest region of an equirectangular environment and the directional light that
+/// comes closest to it. `None` for an environment without any light.
+pub fn key_light(image: &DynamicImage) -> Option<KeyLight> {
+    let image = image.to_rgb32f();
+    let (width, height) = image.dimensions();
+    let grid_width = KEY_LIGHT_GRID_WIDTH.min(width).max(1);
+    let grid_height = (grid_width / 2).min(height).max(1);
+
+    // Average each cell of the grid.
+    let mut grid = vec![Vec3::ZERO; (grid_width * grid_height) as usize];
+    let mut counts = vec![0u32; grid.len()];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let cell = (y * grid_height / height * grid_width + x * grid_width / width) as usize;
+        grid[cell] += Vec3::from(pixel.0);
+        counts[cell] += 1;
+    }
+    for (cell, count) in grid.iter_mut().zip(&counts) {
+        *cell /= (*count).max(1) as f32;
+    }
+
+    let peak = grid.iter().copied().map(luminance).fold(0.0, f32::max);
+    if peak <= 0.0 {
+        return None;
+    }
+
+    // Integrate the bright cells over the solid angle they cover.
+    let cell_solid_angle = |y: u32| {
+        let latitude_step = std::f32::consts::PI / grid_height as f32;
+        std::f32::consts::TAU / grid_width as f32
+            * latitude_step
+            * row_latitude(y, grid_height).cos()
+    };
+    let mut irradiance = Vec3::ZERO;
+    let mut towards = Vec3::ZERO;
+    for y in 0..grid_height {
+        for x in 0..grid_width {
+            let radiance = grid[(y * grid_width + x) as usize];
+            if luminance(radiance) < peak * KEY_LIGHT_THRESHOLD {
+                continue;
+            }
+            let contribution = radiance * cell_solid_angle(y);
+            irradiance += contribution;
+            towards +=
+                pixel_direction(x as f32, y, grid_width, grid_height) * luminance(contribution);
+        }
+    }
+
+    let intensity = irradiance.max_element();
+    if intensity <= 0.0 || towards.length_squared() == 0.0 {
+        return None;
+    }
+    Some(KeyLight {
+        direction: -towards.normalize(),
+        color: irradiance / intensity,
+        intensity,
+        irradiance,
+    })
+}
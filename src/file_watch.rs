@@ -0,0 +1,70 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use web_time::Instant;
+
+/// How often the file's modification time is looked at.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How long the file has to stay unchanged after a change before it counts, so an exporter
+/// writing it in several steps only causes one reload.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches a file for --watch and --puppet-watch by polling its modification time.
+///
+/// Polling from the event loop needs no extra dependency or thread, and keeps working when an
+/// exporter replaces the file by renaming a new one over it, which ends an inotify style watch.
+pub struct FileWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    /// When the last change not reported yet was seen.
+    changed_at: Option<Instant>,
+    next_poll: Instant,
+}
+
+impl FileWatch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = modified(&path);
+        if modified.is_none() {
+            log::warn!(
+                "Can't read the modification time of {}, it won't be reloaded when it changes",
+                path.display()
+            );
+        }
+        Self {
+            path,
+            modified,
+            changed_at: None,
+            next_poll: Instant::now(),
+        }
+    }
+
+    /// Whether the file changed and has been left alone for a moment since.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if now < self.next_poll {
+            return false;
+        }
+        self.next_poll = now + POLL_INTERVAL;
+
+        let modified = modified(&self.path);
+        if modified.is_some() && modified != self.modified {
+            self.modified = modified;
+            self.changed_at = Some(now);
+        }
+        match self.changed_at {
+            Some(changed_at) if now - changed_at >= DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,69 @@
+use std::{collections::VecDeque, time::Duration};
+
+use glam::{Vec2, Vec4};
+
+use crate::overlay::Overlay;
+
+/// Frame time that fills the whole height of the graph (30fps).
+const GRAPH_MAX_MS: f32 = 1000.0 / 30.0;
+/// Frame time of a 60fps frame, bars above it are drawn as a warning.
+const TARGET_MS: f32 = 1000.0 / 60.0;
+
+const GRAPH_SIZE: Vec2 = Vec2::new(300.0, 100.0);
+const GRAPH_MARGIN: f32 = 10.0;
+
+/// Rolling history of the most recent frame times, drawn as a bar chart.
+pub struct FrameTimeGraph {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl FrameTimeGraph {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, delta: Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(delta.as_secs_f32() * 1_000.0);
+    }
+
+    /// Queues the graph into the bottom left corner of a `screen_height` tall screen.
+    pub fn draw(&self, overlay: &mut Overlay, screen_height: f32) {
+        let min = Vec2::new(GRAPH_MARGIN, screen_height - GRAPH_MARGIN - GRAPH_SIZE.y);
+        let max = min + GRAPH_SIZE;
+
+        overlay.rect(min, max, Vec4::new(0.0, 0.0, 0.0, 0.5));
+
+        let bar_width = GRAPH_SIZE.x / self.capacity as f32;
+        for (idx, &ms) in self.samples.iter().enumerate() {
+            let height = (ms / GRAPH_MAX_MS).min(1.0) * GRAPH_SIZE.y;
+            let color = if ms <= TARGET_MS {
+                Vec4::new(0.2, 0.8, 0.2, 0.9)
+            } else if ms <= GRAPH_MAX_MS {
+                Vec4::new(0.9, 0.8, 0.1, 0.9)
+            } else {
+                Vec4::new(0.9, 0.2, 0.1, 0.9)
+            };
+            let x = min.x + idx as f32 * bar_width;
+            overlay.rect(
+                Vec2::new(x, max.y - height),
+                Vec2::new(x + bar_width, max.y),
+                color,
+            );
+        }
+
+        // 60fps reference line
+        let target_y = max.y - TARGET_MS / GRAPH_MAX_MS * GRAPH_SIZE.y;
+        overlay.rect(
+            Vec2::new(min.x, target_y),
+            Vec2::new(max.x, target_y + 1.0),
+            Vec4::new(1.0, 1.0, 1.0, 0.6),
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use gilrs::{Axis, Button, Gilrs};
+use glam::Vec2;
+
+/// Camera controls read from the first connected gamepad.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GamepadInput {
+    /// Left stick, x to the right and y forward.
+    pub movement: Vec2,
+    /// Right stick, x to the right and y up.
+    pub look: Vec2,
+    /// Right trigger minus left trigger.
+    pub vertical: f32,
+    /// Left stick pressed in, moving at run speed.
+    pub run: bool,
+}
+
+impl GamepadInput {
+    pub fn is_idle(&self) -> bool {
+        self.movement == Vec2::ZERO && self.look == Vec2::ZERO && self.vertical == 0.0
+    }
+}
+
+pub struct Gamepad {
+    gilrs: Gilrs,
+    /// Stick deflection below this is ignored, so worn sticks don't drift.
+    dead_zone: f32,
+}
+
+impl Gamepad {
+    pub fn new(dead_zone: f32) -> Result<Self, gilrs::Error> {
+        Ok(Self {
+            gilrs: Gilrs::new()?,
+            dead_zone,
+        })
+    }
+
+    /// The current state of the first connected gamepad, `None` without one.
+    pub fn poll(&mut self) -> Option<GamepadInput> {
+        // gilrs only updates the gamepad state while handing out its events.
+        while self.gilrs.next_event().is_some() {}
+        let (_, gamepad) = self.gilrs.gamepads().next()?;
+
+        let stick = |x, y| {
+            apply_dead_zone(
+                Vec2::new(gamepad.value(x), gamepad.value(y)),
+                self.dead_zone,
+            )
+        };
+        let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+        Some(GamepadInput {
+            movement: stick(Axis::LeftStickX, Axis::LeftStickY),
+            look: stick(Axis::RightStickX, Axis::RightStickY),
+            vertical: trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2),
+            run: gamepad.is_pressed(Button::LeftThumb),
+        })
+    }
+}
+
+/// Zeroes a stick inside the dead zone and rescales the rest, so moving out of it starts slow
+/// instead of jumping.
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    stick * (scaled / length)
+}
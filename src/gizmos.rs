@@ -0,0 +1,121 @@
+use glam::{Mat4, Quat, Vec3};
+use rend3::{
+    types::{MeshHandle, Object, ObjectHandle, ObjectMeshKind},
+    Renderer,
+};
+use rend3_routine::pbr::{AlbedoComponent, PbrMaterial};
+
+use crate::{lights::PunctualLight, shapes::ShapeBuilder};
+
+/// Length of the arrow drawn for directional lights.
+const ARROW_LENGTH: f32 = 2.0;
+const SPHERE_RADIUS: f32 = 0.1;
+/// Length of the cone drawn for spot lights.
+const CONE_LENGTH: f32 = 0.5;
+/// Wider cones than this would be drawn as a flat disk.
+const MAX_CONE_ANGLE: f32 = 80.0 * std::f32::consts::PI / 180.0;
+
+const DIRECTIONAL_COLOR: Vec3 = Vec3::new(1.0, 0.9, 0.2);
+
+/// Unlit markers showing where the lights in the scene are.
+///
+/// Directional lights have no position, so their arrow is anchored at the origin.
+/// Dropping this removes the markers from the scene.
+pub struct LightGizmos {
+    directional: Vec<ObjectHandle>,
+    punctual: Vec<ObjectHandle>,
+}
+
+fn add_gizmo(renderer: &Renderer, mesh: &MeshHandle, color: Vec3, transform: Mat4) -> ObjectHandle {
+    let material = renderer.add_material(PbrMaterial {
+        albedo: AlbedoComponent::Value(color.extend(1.0)),
+        unlit: true,
+        ..PbrMaterial::default()
+    });
+    renderer.add_object(Object {
+        mesh_kind: ObjectMeshKind::Static(mesh.clone()),
+        material,
+        transform,
+    })
+}
+
+fn directional_transform(direction: Vec3) -> Mat4 {
+    Mat4::from_rotation_translation(
+        Quat::from_rotation_arc(Vec3::Y, direction.normalize()),
+        Vec3::ZERO,
+    ) * Mat4::from_scale(Vec3::splat(ARROW_LENGTH))
+}
+
+fn punctual_transform(light: &PunctualLight) -> Mat4 {
+    match light.spot {
+        Some(spot) => {
+            let radius = spot.angle.min(MAX_CONE_ANGLE).tan() * CONE_LENGTH;
+            Mat4::from_rotation_translation(
+                Quat::from_rotation_arc(Vec3::Y, spot.direction),
+                light.position,
+            ) * Mat4::from_scale(Vec3::new(radius, CONE_LENGTH, radius))
+        }
+        None => Mat4::from_translation(light.position),
+    }
+}
+
+impl LightGizmos {
+    pub fn new(
+        renderer: &Renderer,
+        directional: &[Vec3],
+        punctual: &[PunctualLight],
+    ) -> anyhow::Result<Self> {
+        // All shapes point along +Y and get rotated into place.
+        let arrow = renderer.add_mesh(
+            ShapeBuilder::new()
+                .cylinder_y(0.0, 0.75, 0.02, 8)
+                .cone_y(0.75, 1.0, 0.06, 12)
+                .build(renderer.handedness)?,
+        )?;
+        let sphere = renderer.add_mesh(
+            ShapeBuilder::new()
+                .sphere(Vec3::ZERO, SPHERE_RADIUS, 8, 12)
+                .build(renderer.handedness)?,
+        )?;
+        // Tip at the light, opening towards +Y with a unit radius.
+        let cone = renderer.add_mesh(
+            ShapeBuilder::new()
+                .cone_y(1.0, 0.0, 1.0, 16)
+                .build(renderer.handedness)?,
+        )?;
+
+        let directional = directional
+            .iter()
+            .map(|&direction| {
+                add_gizmo(
+                    renderer,
+                    &arrow,
+                    DIRECTIONAL_COLOR,
+                    directional_transform(direction),
+                )
+            })
+            .collect();
+        let punctual = punctual
+            .iter()
+            .map(|light| {
+                let mesh = if light.spot.is_some() { &cone } else { &sphere };
+                add_gizmo(renderer, mesh, light.color, punctual_transform(light))
+            })
+            .collect();
+
+        Ok(Self {
+            directional,
+            punctual,
+        })
+    }
+
+    /// Moves the markers to follow lights changed since creation.
+    pub fn update(&self, renderer: &Renderer, directional: &[Vec3], punctual: &[PunctualLight]) {
+        for (handle, &direction) in self.directional.iter().zip(directional) {
+            renderer.set_object_transform(handle, directional_transform(direction));
+        }
+        for (handle, light) in self.punctual.iter().zip(punctual) {
+            renderer.set_object_transform(handle, punctual_transform(light));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use rend3::util::typedefs::FastHashMap;
+use wgpu_profiler::GpuTimerScopeResult;
+
+struct ScopeTotal {
+    label: String,
+    depth: usize,
+    total_seconds: f64,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    /// In order of first appearance, which keeps nested scopes under their parents.
+    scopes: Vec<ScopeTotal>,
+    index: FastHashMap<String, usize>,
+    frames: u32,
+}
+
+impl Accumulator {
+    fn add_scopes(&mut self, results: &[GpuTimerScopeResult], parent_path: &str, depth: usize) {
+        for result in results {
+            let path = format!("{}/{}", parent_path, result.label);
+            let idx = *self.index.entry(path.clone()).or_insert_with(|| {
+                self.scopes.push(ScopeTotal {
+                    label: result.label.clone(),
+                    depth,
+                    total_seconds: 0.0,
+                });
+                self.scopes.len() - 1
+            });
+            self.scopes[idx].total_seconds += result.time.end - result.time.start;
+
+            self.add_scopes(&result.nested_scopes, &path, depth + 1);
+        }
+    }
+}
+
+/// Per scope averages of gpu timings over the last full stats interval.
+#[derive(Default)]
+pub struct GpuTimingSummary {
+    current: Accumulator,
+    last: Accumulator,
+}
+
+impl GpuTimingSummary {
+    pub fn add_frame(&mut self, results: &[GpuTimerScopeResult]) {
+        self.current.frames += 1;
+        self.current.add_scopes(results, "", 0);
+    }
+
+    /// Finishes the current interval, making it the one that gets printed.
+    pub fn roll(&mut self) {
+        self.last = std::mem::take(&mut self.current);
+    }
+
+    pub fn print(&self) {
+        let last = &self.last;
+        if last.frames == 0 {
+            println!("No gpu timings available, either timestamp queries are unsupported or not enough frames have elapsed yet!");
+            return;
+        }
+
+        println!("Average gpu time per frame over {} frames:", last.frames);
+        for scope in &last.scopes {
+            println!(
+                "{:indent$}{}: {:.3}ms",
+                "",
+                scope.label,
+                scope.total_seconds * 1_000.0 / last.frames as f64,
+                indent = scope.depth * 2 + 2,
+            );
+        }
+    }
+}
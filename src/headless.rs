@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use glam::{Mat4, UVec2, Vec3, Vec4};
+use rend3::{
+    types::{Camera, CameraProjection, DirectionalLight, Handedness, SampleCount, TextureFormat},
+    InstanceAdapterDevice, Renderer,
+};
+use rend3_routine::base::BaseRenderGraph;
+
+/// Number of cubes in the rendered scene, see [`crate::benchmark_scene::spawn_grid`].
+const CUBE_COUNT: u32 = 27;
+/// Textures are copied out in rows aligned to this many bytes.
+const ROW_ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+/// Renders a single frame of the benchmark cube grid without a window, with a fixed camera and
+/// sun, through the same rend3 render graph as the viewer.
+///
+/// Nothing depends on time or input, so the same device renders the same image every time.
+/// Fails when there is no adapter to render with.
+pub fn render_benchmark_frame(size: UVec2) -> anyhow::Result<image::RgbaImage> {
+    let iad = pollster::block_on(rend3::create_iad(None, None, None, None))?;
+    let mut view = OffscreenView::new(iad, size)?;
+    let renderer = view.renderer().clone();
+
+    let _objects = crate::benchmark_scene::spawn_grid(&renderer, CUBE_COUNT)?;
+    let _sun = renderer.add_directional_light(DirectionalLight {
+        color: Vec3::ONE,
+        intensity: 4.0,
+        direction: Vec3::new(-1.0, -4.0, 2.0),
+        distance: 20.0,
+        resolution: 2048,
+    });
+    let eye = Vec3::new(6.0, 5.0, 8.0);
+    renderer.set_camera_data(Camera {
+        projection: CameraProjection::Perspective {
+            vfov: 60.0,
+            near: 0.1,
+        },
+        view: Mat4::look_at_rh(eye, Vec3::ZERO, Vec3::Y),
+    });
+
+    view.render_frame();
+    read_back(&renderer, view.texture(), size)
+}
+
+/// A rend3 renderer drawing into a texture instead of a window, one frame at a time, for
+/// embedding the viewer's rendering in another application.
+///
+/// The texture can be sampled, so a tool built on egui can show it in a panel by registering
+/// [`OffscreenView::view`] with `egui_wgpu::Renderer::register_native_texture` and drawing the
+/// returned `TextureId`. Create the [`InstanceAdapterDevice`] from the tool's own wgpu device to
+/// share it, after each [`OffscreenView::resize`] the view needs registering again.
+pub struct OffscreenView {
+    renderer: Arc<Renderer>,
+    base_rendergraph: BaseRenderGraph,
+    pbr_routine: rend3_routine::pbr::PbrRoutine,
+    tonemapping_routine: rend3_routine::tonemapping::TonemappingRoutine,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    size: UVec2,
+    /// Color of the ambient light, `w` is unused.
+    pub ambient_color: Vec4,
+    pub clear_color: Vec4,
+}
+
+impl OffscreenView {
+    /// Frames are `size` pixels big. Add objects, lights and the camera through
+    /// [`OffscreenView::renderer`].
+    pub fn new(iad: InstanceAdapterDevice, size: UVec2) -> anyhow::Result<Self> {
+        let size = size.max(UVec2::ONE);
+        let renderer = Renderer::new(iad, Handedness::Right, Some(size.x as f32 / size.y as f32))?;
+
+        let mut spp = rend3::ShaderPreProcessor::new();
+        rend3_routine::builtin_shaders(&mut spp);
+        let base_rendergraph = BaseRenderGraph::new(&renderer, &spp);
+        let mut data_core = renderer.data_core.lock();
+        let pbr_routine = rend3_routine::pbr::PbrRoutine::new(
+            &renderer,
+            &mut data_core,
+            &spp,
+            &base_rendergraph.interfaces,
+            &base_rendergraph.gpu_culler.culling_buffer_map_handle,
+        );
+        let tonemapping_routine = rend3_routine::tonemapping::TonemappingRoutine::new(
+            &renderer,
+            &spp,
+            &base_rendergraph.interfaces,
+            FORMAT,
+        );
+        drop(data_core);
+
+        let (texture, view) = create_target(&renderer, size);
+        Ok(Self {
+            renderer,
+            base_rendergraph,
+            pbr_routine,
+            tonemapping_routine,
+            texture,
+            view,
+            size,
+            ambient_color: Vec4::new(0.1, 0.1, 0.1, 1.0),
+            clear_color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+        })
+    }
+
+    pub fn renderer(&self) -> &Arc<Renderer> {
+        &self.renderer
+    }
+
+    /// The texture frames are rendered into, Bgra8Unorm.
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Replaces the texture with one `size` pixels big, when that is a different size.
+    pub fn resize(&mut self, size: UVec2) {
+        let size = size.max(UVec2::ONE);
+        if size == self.size {
+            return;
+        }
+        self.renderer
+            .set_aspect_ratio(size.x as f32 / size.y as f32);
+        (self.texture, self.view) = create_target(&self.renderer, size);
+        self.size = size;
+    }
+
+    /// Applies the changes made through the renderer since the last frame and renders one.
+    pub fn render_frame(&mut self) {
+        profiling::scope!("offscreen frame");
+
+        self.renderer.swap_instruction_buffers();
+        let mut eval_output = self.renderer.evaluate_instructions();
+        let mut graph = rend3::graph::RenderGraph::new();
+        let target_handle = graph.add_imported_render_target(
+            &self.texture,
+            0..1,
+            0..1,
+            rend3::graph::ViewportRect::from_size(self.size),
+        );
+        self.base_rendergraph.add_to_graph(
+            &mut graph,
+            rend3_routine::base::BaseRenderGraphInputs {
+                eval_output: &eval_output,
+                routines: rend3_routine::base::BaseRenderGraphRoutines {
+                    pbr: &self.pbr_routine,
+                    skybox: None,
+                    tonemapping: &self.tonemapping_routine,
+                },
+                target: rend3_routine::base::OutputRenderTarget {
+                    handle: target_handle,
+                    resolution: self.size,
+                    samples: SampleCount::One,
+                },
+            },
+            rend3_routine::base::BaseRenderGraphSettings {
+                ambient_color: self.ambient_color,
+                clear_color: self.clear_color,
+            },
+        );
+        graph.execute(&self.renderer, &mut eval_output);
+    }
+}
+
+/// The same format as the window's surface, so the tonemapping pipeline is the same.
+const FORMAT: TextureFormat = TextureFormat::Bgra8Unorm;
+
+fn create_target(renderer: &Renderer, size: UVec2) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen target"),
+        size: wgpu::Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// Copies a Bgra8Unorm texture into an image, waiting for the gpu to finish. The texture needs
+/// [`wgpu::TextureUsages::COPY_SRC`].
+pub(crate) fn read_back(
+    renderer: &Renderer,
+    texture: &wgpu::Texture,
+    size: UVec2,
+) -> anyhow::Result<image::RgbaImage> {
+    let padded_row = (size.x * 4).div_ceil(ROW_ALIGNMENT) * ROW_ALIGNMENT;
+    let buffer = renderer.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("headless readback"),
+        size: (padded_row * size.y) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = renderer
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("headless readback encoder"),
+        });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    renderer.queue.submit(std::iter::once(encoder.finish()));
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    renderer.device.poll(wgpu::Maintain::Wait);
+    receiver.recv()??;
+
+    let data = buffer.slice(..).get_mapped_range();
+    let pixels = data
+        .chunks_exact(padded_row as usize)
+        .flat_map(|row| row[..(size.x * 4) as usize].chunks_exact(4))
+        .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+        .collect();
+    image::RgbaImage::from_raw(size.x, size.y, pixels)
+        .ok_or_else(|| anyhow::anyhow!("Readback doesn't match the frame size"))
+}
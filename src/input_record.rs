@@ -0,0 +1,166 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    time::Duration,
+};
+
+use glam::{DVec2, UVec2};
+use web_time::Instant;
+
+/// Input that can be recorded and replayed.
+///
+/// Recordings store one input per line as `<microseconds since start> <kind> <values...>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordedInput {
+    Key {
+        scancode: u32,
+        pressed: bool,
+    },
+    MouseMotion {
+        delta: DVec2,
+    },
+    /// Cursor movement while dragging to look around without a pointer grab.
+    DragLook {
+        delta: DVec2,
+    },
+    LeftClick,
+    /// Drag turning the camera around the orbit pivot.
+    Orbit {
+        delta: DVec2,
+    },
+    /// Drag moving the camera sideways and up or down.
+    Pan {
+        delta: DVec2,
+    },
+    /// Drag or scroll moving the camera towards or away from the orbit pivot, or changing the
+    /// field of view while flying, in zoom steps.
+    Zoom {
+        steps: f64,
+    },
+    Resize {
+        size: UVec2,
+    },
+}
+
+impl RecordedInput {
+    fn write(&self, out: &mut impl Write, time: Duration) -> io::Result<()> {
+        let micros = time.as_micros();
+        match *self {
+            Self::Key { scancode, pressed } => {
+                writeln!(out, "{} key {} {}", micros, scancode, pressed as u8)
+            }
+            Self::MouseMotion { delta } => {
+                writeln!(out, "{} motion {} {}", micros, delta.x, delta.y)
+            }
+            Self::DragLook { delta } => {
+                writeln!(out, "{} drag {} {}", micros, delta.x, delta.y)
+            }
+            Self::LeftClick => writeln!(out, "{} click", micros),
+            Self::Orbit { delta } => writeln!(out, "{} orbit {} {}", micros, delta.x, delta.y),
+            Self::Pan { delta } => writeln!(out, "{} pan {} {}", micros, delta.x, delta.y),
+            Self::Zoom { steps } => writeln!(out, "{} zoom {}", micros, steps),
+            Self::Resize { size } => writeln!(out, "{} resize {} {}", micros, size.x, size.y),
+        }
+    }
+
+    fn parse(line: &str) -> Option<(Duration, Self)> {
+        let mut parts = line.split_whitespace();
+        let time = Duration::from_micros(parts.next()?.parse().ok()?);
+        let input = match parts.next()? {
+            "key" => Self::Key {
+                scancode: parts.next()?.parse().ok()?,
+                pressed: parts.next()? == "1",
+            },
+            "motion" => Self::MouseMotion {
+                delta: DVec2::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?),
+            },
+            "drag" => Self::DragLook {
+                delta: DVec2::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?),
+            },
+            "click" => Self::LeftClick,
+            "orbit" => Self::Orbit {
+                delta: DVec2::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?),
+            },
+            "pan" => Self::Pan {
+                delta: DVec2::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?),
+            },
+            "zoom" => Self::Zoom {
+                steps: parts.next()?.parse().ok()?,
+            },
+            "resize" => Self::Resize {
+                size: UVec2::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?),
+            },
+            _ => return None,
+        };
+        Some((time, input))
+    }
+}
+
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl InputRecorder {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, input: RecordedInput) {
+        let result = input
+            .write(&mut self.writer, self.start.elapsed())
+            // Flush every input so a crash doesn't take the interesting part of the recording with it.
+            .and_then(|()| self.writer.flush());
+        if let Err(e) = result {
+            log::warn!("Failed to record input: {}", e);
+        }
+    }
+}
+
+pub struct InputReplay {
+    inputs: VecDeque<(Duration, RecordedInput)>,
+    start: Instant,
+}
+
+impl InputReplay {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let mut inputs = VecDeque::new();
+        for (idx, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match RecordedInput::parse(&line) {
+                Some(input) => inputs.push_back(input),
+                None => log::warn!("{}:{}: skipping malformed input '{}'", path, idx + 1, line),
+            }
+        }
+
+        Ok(Self {
+            inputs,
+            start: Instant::now(),
+        })
+    }
+
+    /// Removes and returns every input whose time has come.
+    pub fn due(&mut self) -> Vec<RecordedInput> {
+        let elapsed = self.start.elapsed();
+        let mut due = Vec::new();
+        while let Some(&(time, input)) = self.inputs.front() {
+            if time > elapsed {
+                break;
+            }
+            due.push(input);
+            self.inputs.pop_front();
+        }
+        due
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
@@ -0,0 +1,77 @@
+use rend3::util::typedefs::FastHashMap;
+
+use crate::platform::Scancodes;
+
+/// Keys held to move the camera, which --bind can move to other scancodes for keyboard
+/// layouts where the QWERTY positions make no sense.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Up,
+    Run,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::Forward,
+        Action::Back,
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Run,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Forward => "forward",
+            Action::Back => "back",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Up => "up",
+            Action::Run => "run",
+        }
+    }
+
+    fn default_scancode(self) -> u32 {
+        match self {
+            Action::Forward => Scancodes::W,
+            Action::Back => Scancodes::S,
+            Action::Left => Scancodes::A,
+            Action::Right => Scancodes::D,
+            Action::Up => Scancodes::Q,
+            Action::Run => Scancodes::SHIFT,
+        }
+    }
+}
+
+/// The default bindings with `overrides` applied on top.
+pub fn key_bindings(overrides: &[(Action, u32)]) -> FastHashMap<Action, u32> {
+    let mut bindings: FastHashMap<Action, u32> = Action::ALL
+        .iter()
+        .map(|&action| (action, action.default_scancode()))
+        .collect();
+    bindings.extend(overrides.iter().copied());
+    bindings
+}
+
+/// Parses `action=scancode`, with the scancode in decimal or in hex starting with 0x like the
+/// scancodes logged on key presses.
+pub fn parse(value: &str) -> Result<(Action, u32), &'static str> {
+    let (name, scancode) = value
+        .split_once('=')
+        .ok_or("Bindings are defined as action=scancode")?;
+    let action = Action::ALL
+        .into_iter()
+        .find(|action| action.name() == name.trim())
+        .ok_or("Actions are forward, back, left, right, up and run")?;
+    let scancode = scancode.trim();
+    let scancode = match scancode.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => scancode.parse(),
+    }
+    .map_err(|_| "Cannot parse scancode")?;
+    Ok((action, scancode))
+}
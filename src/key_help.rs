@@ -0,0 +1,80 @@
+use glam::{Vec2, Vec4};
+
+use crate::overlay::Overlay;
+
+/// Every runtime key and what it does, in the order they are listed on screen.
+/// Add a line here along with the handler when binding a new key.
+pub const KEY_BINDINGS: &[(&str, &str)] = &[
+    ("F1", "Show or hide this help"),
+    ("W A S D", "Move"),
+    ("Q", "Move up"),
+    ("Shift", "Move at run speed"),
+    ("Mouse", "Look around while the pointer is grabbed"),
+    ("Left drag", "Look around while the pointer isn't grabbed"),
+    ("Left click", "Grab the pointer"),
+    ("Escape", "Release the pointer"),
+    ("G", "Toggle pointer grab"),
+    ("O", "Toggle orbit navigation around a pivot"),
+    ("Left drag", "Orbit around the pivot in orbit mode"),
+    ("Middle drag", "Pan"),
+    ("Right drag / Wheel", "Zoom towards the pivot in orbit mode"),
+    ("Wheel", "Change the field of view while flying"),
+    ("Wheel", "Change the view size in orthographic mode"),
+    ("T / Y", "Move the sun backwards / forwards in time"),
+    ("[ / ]", "Lower / raise the ambient light"),
+    ("L", "Toggle light gizmos"),
+    ("H", "Toggle the --puppet"),
+    ("; / '", "Shrink / grow the --puppet"),
+    ("Space", "Pause or resume the --animation"),
+    ("Minus / Equal", "Change the animation blend"),
+    ("1 / 2", "Lower / raise the roughness of every material"),
+    ("3 / 4", "Lower / raise the metallic of every material"),
+    ("0", "Reset the materials"),
+    ("B", "Toggle the skybox"),
+    ("V", "Cycle the present modes the surface supports"),
+    ("C", "Switch the --compare view between split, wipe and off"),
+    ("Left drag", "Move the --compare wipe line when on it"),
+    ("F2", "Toggle the wireframe"),
+    ("F3", "Toggle the frame time graph"),
+    ("F4", "Toggle the directional shadow bounds"),
+    ("F5", "Reset the camera and everything above"),
+    ("F6", "Toggle the coordinates under the cursor"),
+    ("F7", "Switch between perspective and orthographic"),
+    ("F9", "Freeze or resume animations and the puppet"),
+    ("F12", "Freeze time and save the frame to capture-NNNN.png"),
+    ("I", "Print gpu timings of the last second"),
+    ("P", "Write a gpu timing trace to profile.json"),
+    ("Period", "Print the camera position"),
+    ("K", "Look at the --look-at point"),
+    ("F", "Frame the loaded scene"),
+    ("N", "Cycle through the gltf cameras and the free camera"),
+    ("Tab", "Switch to the next of the gltf files"),
+];
+
+const TEXT_SCALE: f32 = 2.0;
+const PADDING: f32 = 12.0;
+/// Space between the key column and the action column, in characters.
+const COLUMN_GAP: usize = 3;
+
+/// Queues the list of key bindings into the middle of the screen.
+pub fn draw(overlay: &mut Overlay, screen: Vec2) {
+    let key_width = KEY_BINDINGS
+        .iter()
+        .map(|(key, _)| key.len())
+        .max()
+        .unwrap_or(0);
+    let text: String = KEY_BINDINGS
+        .iter()
+        .map(|(key, action)| format!("{:width$}{}\n", key, action, width = key_width + COLUMN_GAP))
+        .collect();
+    let text = text.trim_end();
+
+    let size = Overlay::text_size(text, TEXT_SCALE);
+    let min = ((screen - size) / 2.0).round();
+    overlay.rect(
+        min - PADDING,
+        min + size + PADDING,
+        Vec4::new(0.0, 0.0, 0.0, 0.75),
+    );
+    overlay.text(min, TEXT_SCALE, Vec4::ONE, text);
+}
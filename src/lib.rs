@@ -1,67 +1,335 @@
 use std::{
-    collections::HashMap, future::Future, hash::BuildHasher, path::Path, process::exit, sync::Arc,
+    collections::{HashMap, VecDeque},
+    ffi::OsString,
+    future::Future,
+    hash::BuildHasher,
+    path::Path,
+    process::exit,
+    str::FromStr,
+    sync::{mpsc, Arc},
     time::Duration,
 };
 
-use glam::{uvec2, vec2, DVec2, Mat3A, Mat4, UVec2, Vec2, Vec3, Vec3A};
+#[cfg(feature = "puppet")]
+use glam::{uvec2, vec2};
+use glam::{DVec2, Mat3A, Mat4, UVec2, Vec2, Vec3, Vec3A, Vec4};
+#[cfg(feature = "puppet")]
 use inox2d::formats::inp::parse_inp;
 use log::{info, logger, warn};
 use pico_args::Arguments;
+#[cfg(feature = "skybox")]
+use rend3::types::Texture;
 use rend3::{
     types::{
-        Backend, Camera, CameraProjection, DirectionalLight, DirectionalLightHandle, Handedness,
-        SampleCount, Texture, TextureFormat,
+        Backend, Camera, CameraProjection, DirectionalLight, DirectionalLightChange,
+        DirectionalLightHandle, Handedness, PointLightHandle, SampleCount, TextureFormat,
     },
     util::typedefs::FastHashMap,
     Renderer, RendererProfile,
 };
 use rend3_framework::{lock, App as _, AssetPath, Event, Mutex, UserResizeEvent};
+#[cfg(feature = "gltf")]
 use rend3_gltf::GltfSceneInstance;
-use rend3_routine::{base::BaseRenderGraph, pbr::NormalTextureYDirection, skybox::SkyboxRoutine};
+use rend3_routine::base::BaseRenderGraph;
+#[cfg(feature = "gltf")]
+use rend3_routine::pbr::NormalTextureYDirection;
+#[cfg(feature = "skybox")]
+use rend3_routine::skybox::SkyboxRoutine;
 use web_time::Instant;
-use wgpu::{Extent3d, Features, Surface};
+#[cfg(feature = "puppet")]
+use wgpu::Extent3d;
+use wgpu::{Features, Surface};
 use wgpu_profiler::GpuTimerScopeResult;
 #[cfg(target_arch = "wasm32")]
 use winit::keyboard::PhysicalKey::Code;
 #[cfg(not(target_arch = "wasm32"))]
 use winit::platform::scancode::PhysicalKeyExtScancode;
 use winit::{
-    event::{DeviceEvent, ElementState, KeyEvent, MouseButton, WindowEvent},
+    event::{DeviceEvent, ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
     event_loop::EventLoopWindowTarget,
     window::{Fullscreen, Window, WindowBuilder},
 };
 
+#[cfg(feature = "gltf")]
+mod animation;
+#[cfg(feature = "gltf")]
+mod asset_fetch;
+mod auto_exposure;
+mod benchmark_scene;
+mod camera_log;
+#[cfg(not(target_arch = "wasm32"))]
+mod camera_script;
+#[cfg(feature = "gltf")]
+mod color_space;
+mod crossfade;
+mod debug_lines;
+#[cfg(feature = "gltf")]
+mod depth_of_field;
+mod environment;
+#[cfg(all(any(feature = "gltf", feature = "puppet"), not(target_arch = "wasm32")))]
+mod file_watch;
+mod font;
+mod frame_graph;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod gizmos;
+mod gpu_timings;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
+mod input_record;
+mod key_bindings;
+mod key_help;
+mod lights;
+#[cfg(feature = "gltf")]
+mod material_override;
+#[cfg(feature = "gltf")]
+mod mesh_validation;
+#[cfg(feature = "gltf")]
+mod morph;
+mod overlay;
+#[cfg(feature = "gltf")]
+mod picking;
 mod platform;
+mod power;
+#[cfg(feature = "gltf")]
+mod scene_info;
+#[cfg(feature = "gltf")]
+mod scene_stats;
+mod shapes;
+#[cfg(feature = "skybox")]
+mod skybox;
+mod sun;
+#[cfg(feature = "gltf")]
+mod vrm;
+#[cfg(feature = "gltf")]
+mod wireframe;
+
+/// How many hours of simulated time pass per second while scrubbing the sun.
+const SUN_SCRUB_HOURS_PER_SECOND: f32 = 2.0;
+/// Scale of the --puppet when --puppet-scale isn't given, and the factor a press of ; or '
+/// changes it by.
+#[cfg(feature = "puppet")]
+const PUPPET_SCALE: f32 = 0.12;
+#[cfg(feature = "puppet")]
+const PUPPET_SCALE_STEP: f32 = 1.25;
+/// Solar time --daycycle starts at when --sun-time isn't given.
+const DAYCYCLE_START_TIME: f32 = 6.0;
+/// Fraction of the --ambient level left at night during --daycycle.
+const DAYCYCLE_NIGHT_AMBIENT: f32 = 0.2;
+
+/// How fast holding Minus/Equal moves the animation blend, as a fraction per second.
+#[cfg(feature = "gltf")]
+const ANIMATION_BLEND_PER_SECOND: f32 = 0.5;
+
+/// Where the scene from --compare is placed. Both scenes share one world, so it is moved far
+/// enough away to be a speck at most from the other, without losing much float precision.
+#[cfg(feature = "gltf")]
+const COMPARE_OFFSET: Vec3 = Vec3::new(10_000.0, 0.0, 0.0);
+
+/// How much a press of [ or ] changes the ambient light level, and the highest it goes.
+const AMBIENT_STEP: f32 = 0.05;
+const MAX_AMBIENT: f32 = 2.0;
+
+/// How much a press of 1 to 4 changes the material roughness or metallic adjustment.
+#[cfg(feature = "gltf")]
+const MATERIAL_ADJUSTMENT_STEP: f32 = 0.1;
+
+/// How far from the wipe line in pixels a press still picks it up.
+const WIPE_GRAB_DISTANCE: f64 = 6.0;
+
+/// How much faster orbiting turns the camera than dragging to look around.
+const ORBIT_DRAG_SCALE: f64 = 4.0;
+/// Distance to the orbit pivot when there is no --look-at point to orbit around.
+const ORBIT_DEFAULT_DISTANCE: f32 = 10.0;
+/// Half the length of the axes marking the orbit pivot, as a fraction of the distance to it.
+const ORBIT_PIVOT_MARKER_SIZE: f32 = 0.03;
+/// Shadow map size of the --directional-light. --shadow-resolution only applies to gltf lights.
+const DIRECTIONAL_SHADOW_RESOLUTION: u16 = 2048;
+const SHADOW_BOUNDS_COLOR: Vec4 = Vec4::new(1.0, 0.8, 0.2, 1.0);
+/// Panning in orbit mode moves the camera by this fraction of the pivot distance per pixel dragged.
+const PAN_PER_PIXEL: f32 = 0.002;
+/// How fast the right stick of a gamepad turns the camera at full tilt, in radians per second.
+#[cfg(feature = "gamepad")]
+const GAMEPAD_LOOK_SPEED: f32 = 2.5;
+
+/// Panning while flying moves the camera as far per pixel dragged as walking does in this many
+/// seconds.
+const FLY_PAN_SECONDS_PER_PIXEL: f32 = 0.002;
+/// Each zoom step changes the pivot distance by this factor.
+const ZOOM_STEP_FACTOR: f32 = 1.1;
+/// Dragging this many pixels with the right mouse button is one zoom step, like one wheel notch.
+const PIXELS_PER_ZOOM_STEP: f64 = 20.0;
+/// While flying, each zoom step changes the vertical field of view by this many degrees.
+const FOV_ZOOM_STEP: f32 = 5.0;
+/// Field of view the wheel and --fov stay within, in degrees.
+const FOV_RANGE: std::ops::RangeInclusive<f32> = 10.0..=120.0;
+/// How quickly the field of view follows the wheel, higher is faster.
+const FOV_ZOOM_SPEED: f32 = 12.0;
+/// Depth of the box an orthographic camera sees, centered on the camera.
+const ORTHO_DEPTH: f32 = 10_000.0;
+/// Smallest height of the orthographic view, so zooming in can't make it empty.
+const MIN_ORTHO_HEIGHT: f32 = 0.001;
+/// Scene time a --bench frame advances by, in seconds.
+const BENCH_TIME_STEP: f32 = 1.0 / 60.0;
+/// Closest zooming gets to the pivot, so the camera can't end up on it.
+const MIN_ORBIT_DISTANCE: f32 = 0.01;
+/// --suggest-flags suggests a --scale for scenes whose largest side is outside this range, to
+/// make it [`SUGGESTED_SCENE_SIZE`] instead.
+#[cfg(feature = "gltf")]
+const SUGGESTED_SCENE_SIZES: std::ops::RangeInclusive<f32> = 0.1..=1000.0;
+#[cfg(feature = "gltf")]
+const SUGGESTED_SCENE_SIZE: f32 = 10.0;
+
+const LOADING_TEXT: &str = "Loading...";
+const LOADING_TEXT_SCALE: f32 = 4.0;
+/// Size of the coordinate readout next to the cursor.
+#[cfg(feature = "gltf")]
+const COORDINATE_TEXT_SCALE: f32 = 2.0;
+const LOADING_BACKGROUND: Vec4 = Vec4::new(0.05, 0.05, 0.07, 1.0);
+
+/// Lowest internal resolution scale --target-fps may pick.
+const MIN_RENDER_SCALE: f32 = 0.25;
+/// Frame rate and highest internal resolution scale while saving power.
+const POWER_SAVER_FPS: f32 = 30.0;
+const POWER_SAVER_RENDER_SCALE: f32 = 0.75;
+/// How often `--power-saver auto` checks whether the machine is on battery.
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+fn default_render_scale() -> f32 {
+    // Android has very low memory bandwidth, so lets run internal buffers at half
+    // res by default
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "android")] {
+            0.5
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Decodes the image of the skybox face `face` from `directory`, or the bundled skybox, trying
+/// each of [`skybox::EXTENSIONS`].
+#[cfg(feature = "skybox")]
+async fn load_skybox_image(
+    loader: &rend3_framework::AssetLoader,
+    directory: Option<&str>,
+    face: &str,
+    flip: skybox::FaceFlip,
+) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    let mut errors = Vec::new();
+    for extension in skybox::EXTENSIONS {
+        let path = match directory {
+            Some(directory) => {
+                format!("{}/{}.{}", directory.trim_end_matches('/'), face, extension)
+            }
+            None => format!("skybox/{}.{}", face, extension),
+        };
+        let asset = match directory {
+            Some(_) => AssetPath::External(&path),
+            None => AssetPath::Internal(&path),
+        };
+        match loader.get_asset(asset).await {
+            Ok(data) => {
+                let mut decoded = image::load_from_memory(&data)
+                    .map_err(|e| format!("Error {}: {}", path, e))?
+                    .into_rgba8();
+                flip.apply(&mut decoded);
+                return Ok(decoded);
+            }
+            Err(e) => errors.push(format!("Error {}: {}", path, e)),
+        }
+    }
+    Err(errors.join(", ").into())
+}
+
+/// Loads the six face images of the skybox, returning their data in cube face order and their
+/// size.
+#[cfg(feature = "skybox")]
+async fn load_skybox_faces(
+    loader: &rend3_framework::AssetLoader,
+    layout: &skybox::SkyboxLayout,
+) -> Result<(Vec<u8>, UVec2), Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    let mut size = None;
+    for (&source, &flip) in layout.sources.iter().zip(&layout.flips) {
+        let name = skybox::FACE_NAMES[source];
+        let face = load_skybox_image(loader, layout.directory.as_deref(), name, flip).await?;
+        let face_size = UVec2::new(face.width(), face.height());
+        // Every face is the same square, the size of the first one.
+        match size {
+            None if face_size.x != face_size.y => {
+                return Err(format!(
+                    "Skybox face {} is {}x{}, cube faces must be square",
+                    name, face_size.x, face_size.y
+                )
+                .into());
+            }
+            None => size = Some(face_size),
+            Some(size) if size != face_size => {
+                return Err(format!(
+                    "Skybox face {} is {}x{}, but the faces before it are {}x{}",
+                    name, face_size.x, face_size.y, size.x, size.y
+                )
+                .into());
+            }
+            Some(_) => {}
+        }
+        data.extend_from_slice(face.as_raw());
+    }
+    Ok((data, size.unwrap()))
+}
 
-async fn load_skybox_image(loader: &rend3_framework::AssetLoader, data: &mut Vec<u8>, path: &str) {
-    let decoded = image::load_from_memory(
+/// Loads the --skybox-equirect panorama and resamples it into cube faces, returning their data
+/// in cube face order and their size.
+#[cfg(feature = "skybox")]
+async fn load_skybox_equirect(
+    loader: &rend3_framework::AssetLoader,
+    layout: &skybox::SkyboxLayout,
+    path: &str,
+) -> Result<(Vec<u8>, UVec2), Box<dyn std::error::Error>> {
+    let panorama = image::load_from_memory(
         &loader
-            .get_asset(AssetPath::Internal(path))
+            .get_asset(AssetPath::External(path))
             .await
-            .unwrap_or_else(|e| panic!("Error {}: {}", path, e)),
+            .map_err(|e| format!("Error {}: {}", path, e))?,
     )
-    .unwrap()
-    .into_rgba8();
-
-    data.extend_from_slice(decoded.as_raw());
+    .map_err(|e| format!("Error {}: {}", path, e))?;
+    let resolution = layout.resolution.unwrap_or(panorama.width() / 4).max(1);
+    let faces = {
+        profiling::scope!("equirect to cube");
+        skybox::equirect_to_cube(&panorama, resolution, layout.srgb)
+    };
+    let mut data = Vec::new();
+    for (&source, &flip) in layout.sources.iter().zip(&layout.flips) {
+        let mut face = faces[source].clone();
+        flip.apply(&mut face);
+        data.extend_from_slice(face.as_raw());
+    }
+    Ok((data, UVec2::splat(resolution)))
 }
 
+#[cfg(feature = "skybox")]
 async fn load_skybox(
     renderer: &Arc<Renderer>,
     loader: &rend3_framework::AssetLoader,
     skybox_routine: &Mutex<SkyboxRoutine>,
+    layout: &skybox::SkyboxLayout,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut data = Vec::new();
-    load_skybox_image(loader, &mut data, "skybox/right.jpg").await;
-    load_skybox_image(loader, &mut data, "skybox/left.jpg").await;
-    load_skybox_image(loader, &mut data, "skybox/top.jpg").await;
-    load_skybox_image(loader, &mut data, "skybox/bottom.jpg").await;
-    load_skybox_image(loader, &mut data, "skybox/front.jpg").await;
-    load_skybox_image(loader, &mut data, "skybox/back.jpg").await;
+    let (data, size) = match layout.equirect {
+        Some(ref path) => load_skybox_equirect(loader, layout, path).await?,
+        None => load_skybox_faces(loader, layout).await?,
+    };
 
+    // The images are decoded as rgba. Photos are sRGB encoded, so unless told otherwise let the
+    // sampler convert them to linear before they get lit and tonemapped.
+    let format = if layout.srgb {
+        TextureFormat::Rgba8UnormSrgb
+    } else {
+        TextureFormat::Rgba8Unorm
+    };
     let handle = renderer.add_texture_cube(Texture {
-        format: TextureFormat::Bgra8Unorm,
-        size: UVec2::new(2048, 2048),
+        format,
+        size,
         data,
         label: Some("background".into()),
         mip_count: rend3::types::MipmapCount::ONE,
@@ -71,15 +339,221 @@ async fn load_skybox(
     Ok(())
 }
 
+#[cfg(feature = "gltf")]
+struct LoadedGltf {
+    path: String,
+    scene: rend3_gltf::LoadedGltfScene,
+    /// The objects of the scene, `None` while it is hidden for another of the files.
+    instance: Option<GltfSceneInstance>,
+    /// Where the scene is placed, to instance it again with.
+    transform: Mat4,
+    /// Parsed separately from rend3-gltf, for the information it doesn't keep.
+    document: Option<gltf::Document>,
+    /// Triangles for the wireframe, depth of field and picking, see
+    /// [`wireframe::collect_triangles`].
+    triangles: (Vec<Vec3>, Vec<u32>),
+    /// `None` when the document couldn't be parsed.
+    stats: Option<scene_stats::SceneStats>,
+    /// Base color and emissive textures uploaded again for --base-color-space, by image index.
+    color_textures: HashMap<usize, rend3::types::TextureHandle>,
+}
+
+/// How the --compare scene is shown next to the main scene.
+#[cfg(feature = "gltf")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareMode {
+    /// Side by side, each on its own half of the screen.
+    Split,
+    /// Over each other, the main scene left of a draggable line and the compared one right of it.
+    Wipe,
+    /// Only the main scene.
+    Off,
+}
+
+#[cfg(feature = "gltf")]
+impl CompareMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Split => Self::Wipe,
+            Self::Wipe => Self::Off,
+            Self::Off => Self::Split,
+        }
+    }
+}
+
+/// Where the viewer's camera is and how it is turned.
+#[derive(Debug, Clone, Copy)]
+struct CameraPose {
+    location: Vec3A,
+    pitch: f32,
+    yaw: f32,
+    /// Vertical field of view in degrees.
+    fov: f32,
+}
+
+impl CameraPose {
+    /// In between `self` at 0 and `other` at 1, turning the short way around.
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let yaw_change = (other.yaw - self.yaw + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        Self {
+            location: self.location.lerp(other.location, t),
+            pitch: self.pitch + (other.pitch - self.pitch) * t,
+            yaw: (self.yaw + yaw_change * t).rem_euclid(std::f32::consts::TAU),
+            fov: self.fov + (other.fov - self.fov) * t,
+        }
+    }
+}
+
+/// A camera jump being eased over --camera-transition.
+struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    /// Seconds since the jump.
+    elapsed: f32,
+}
+
+/// How the camera projects the scene onto the screen, switched with F7.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProjectionMode {
+    Perspective,
+    /// Parallel lines stay parallel and sizes don't shrink with distance, for checking
+    /// proportions and 2D-style captures.
+    Orthographic,
+}
+
+/// A part of the frame and the camera it is rendered with.
+struct View {
+    viewport: rend3::graph::ViewportRect,
+    /// Added to the camera location.
+    camera_offset: Vec3,
+    projection: CameraProjection,
+}
+
+/// The matrix rend3 builds out of `projection` for a target with the given aspect ratio.
+fn projection_to_matrix(
+    projection: CameraProjection,
+    aspect_ratio: f32,
+    handedness: Handedness,
+) -> Mat4 {
+    match (projection, handedness) {
+        (CameraProjection::Perspective { vfov, near }, Handedness::Left) => {
+            Mat4::perspective_infinite_reverse_lh(vfov.to_radians(), aspect_ratio, near)
+        }
+        (CameraProjection::Perspective { vfov, near }, Handedness::Right) => {
+            Mat4::perspective_infinite_reverse_rh(vfov.to_radians(), aspect_ratio, near)
+        }
+        (CameraProjection::Orthographic { size }, Handedness::Left) => {
+            let half = size * 0.5;
+            Mat4::orthographic_lh(-half.x, half.x, -half.y, half.y, half.z, -half.z)
+        }
+        (CameraProjection::Orthographic { size }, Handedness::Right) => {
+            let half = size * 0.5;
+            Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, half.z, -half.z)
+        }
+        (CameraProjection::Raw(projection), _) => projection,
+    }
+}
+
+/// `projection` narrowed to the columns `start..end` of a `width` wide frame, so that a viewport
+/// over just those columns shows exactly what they show as part of the whole frame.
+#[cfg(feature = "gltf")]
+fn crop_projection(projection: Mat4, start: u32, end: u32, width: u32) -> Mat4 {
+    let left = start as f32 / width as f32 * 2.0 - 1.0;
+    let right = end as f32 / width as f32 * 2.0 - 1.0;
+    Mat4::from_translation(Vec3::new(-(left + right) / (right - left), 0.0, 0.0))
+        * Mat4::from_scale(Vec3::new(2.0 / (right - left), 1.0, 1.0))
+        * projection
+}
+
+/// Whether the box from `min` to `max` is entirely outside the view frustum of `view_proj`.
+/// Conservative: boxes next to a corner of the frustum can count as inside.
+#[cfg(feature = "gltf")]
+fn bounds_outside_frustum(view_proj: Mat4, min: Vec3, max: Vec3) -> bool {
+    let corners: Vec<Vec4> = (0..8)
+        .map(|corner| {
+            let select = |bit, axis: usize| {
+                if corner & bit == 0 {
+                    min[axis]
+                } else {
+                    max[axis]
+                }
+            };
+            view_proj * Vec4::new(select(1, 0), select(2, 1), select(4, 2), 1.0)
+        })
+        .collect();
+    // Reverse z puts the near plane at z = w and the far plane at infinity.
+    let outside: [fn(Vec4) -> bool; 5] = [
+        |c| c.x < -c.w,
+        |c| c.x > c.w,
+        |c| c.y < -c.w,
+        |c| c.y > c.w,
+        |c| c.z > c.w,
+    ];
+    outside
+        .iter()
+        .any(|plane| corners.iter().all(|&corner| plane(corner)))
+}
+
+/// When to render at a lower frame rate and resolution to save power.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerSaver {
+    Off,
+    On,
+    /// While running on battery.
+    Auto,
+}
+
+/// What to do when something fails after startup, like loading a file or writing an output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorPolicy {
+    /// Log the error and keep the window open, for interactive use.
+    KeepAlive,
+    /// Exit with a non-zero code, for scripts and pipelines.
+    Quit,
+}
+
+impl ErrorPolicy {
+    fn report(self, message: impl std::fmt::Display) {
+        log::error!("{}", message);
+        if self == Self::Quit {
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Sent from the loading task back to the event loop.
+enum LoadEvent {
+    #[cfg(feature = "gltf")]
+    Download(asset_fetch::DownloadProgress),
+    #[cfg(feature = "gltf")]
+    Scene(Box<LoadedGltf>),
+    #[cfg(feature = "gltf")]
+    CompareScene(Box<LoadedGltf>),
+    /// One of the files after the first, which stays hidden until Tab switches to it.
+    #[cfg(feature = "gltf")]
+    OtherScene(Box<LoadedGltf>),
+    /// Everything has been loaded, or failed to.
+    Finished,
+}
+
+#[cfg(feature = "gltf")]
+#[allow(clippy::too_many_arguments)]
 async fn load_gltf(
     renderer: &Arc<Renderer>,
     loader: &rend3_framework::AssetLoader,
     settings: &rend3_gltf::GltfLoadSettings,
     location: AssetPath<'_>,
-) -> Option<(rend3_gltf::LoadedGltfScene, GltfSceneInstance)> {
+    transform: Mat4,
+    center: Option<Vec3>,
+    color_space: color_space::ColorSpace,
+    validate: bool,
+    morph_weights: &[morph::MorphWeight],
+    progress: &mpsc::Sender<LoadEvent>,
+) -> Result<LoadedGltf, Box<dyn std::error::Error>> {
     // profiling::scope!("loading gltf");
     let gltf_start = Instant::now();
-    let is_default_scene = matches!(location, AssetPath::Internal(_));
     let path = loader.get_asset_path(location);
     let path = Path::new(&*path);
     let parent = path.parent().unwrap();
@@ -87,64 +561,272 @@ async fn load_gltf(
     let parent_str = parent.to_string_lossy();
     let path_str = path.as_os_str().to_string_lossy();
     log::info!("Reading gltf file: {}", path_str);
-    let gltf_data_result = loader.get_asset(AssetPath::External(&path_str)).await;
-
-    let gltf_data = match gltf_data_result {
-        Ok(d) => d,
-        Err(_) if is_default_scene => {
-            let suffix = if cfg!(target_os = "windows") {
-                ".exe"
-            } else {
-                ""
-            };
-
-            indoc::eprintdoc!("
-                *** WARNING ***
-
-                It appears you are running scene-viewer with no file to display.
-                
-                The default scene is no longer bundled into the repository. If you are running on git, use the following commands
-                to download and unzip it into the right place. If you're running it through not-git, pass a custom folder to the -C argument
-                to tar, then run scene-viewer path/to/scene.gltf.
-                
-                curl{0} https://cdn.cwfitz.com/scenes/rend3-default-scene.tar -o ./examples/scene-viewer/resources/rend3-default-scene.tar
-                tar{0} xf ./examples/scene-viewer/resources/rend3-default-scene.tar -C ./examples/scene-viewer/resources
-
-                ***************
-            ", suffix);
-
-            return None;
-        }
-        e => e.unwrap(),
+    let mut report_progress = |p| {
+        let _ = progress.send(LoadEvent::Download(p));
     };
+    let gltf_data_result =
+        asset_fetch::fetch(loader, AssetPath::External(&path_str), &mut report_progress).await;
+
+    let gltf_data =
+        gltf_data_result.map_err(|e| format!("Failed to read gltf file {}: {}", path_str, e))?;
 
     let gltf_elapsed = gltf_start.elapsed();
     let resources_start = Instant::now();
-    let (scene, instance) = rend3_gltf::load_gltf(renderer, &gltf_data, settings, |uri| async {
-        if let Some(base64) = rend3_gltf::try_load_base64(&uri) {
+    let mut file = gltf::Gltf::from_slice_without_validation(&gltf_data)
+        .map_err(|e| format!("Failed to parse gltf file {}: {}", path_str, e))?;
+    // Checked before loading, as rend3-gltf may fail on them or leave them out without a word.
+    for unsupported in scene_info::unsupported_primitives(&file) {
+        log::warn!(
+            "rend3 only renders triangles, {} {:?} primitives in meshes {} won't show up",
+            unsupported.count,
+            unsupported.mode,
+            unsupported.meshes.join(", ")
+        );
+    }
+    // rend3 only renders the base shape, so --morph-weight is baked into the buffers before
+    // rend3-gltf reads them, and the closure below hands out the baked ones.
+    let mut baked_buffers = HashMap::<String, Vec<u8>>::new();
+    if !morph_weights.is_empty() {
+        let mut buffers = Vec::new();
+        for buffer in file.buffers() {
+            buffers.push(match buffer.source() {
+                gltf::buffer::Source::Uri(uri) => match rend3_gltf::try_load_base64(uri) {
+                    Some(data) => data,
+                    None => {
+                        let full_uri = format!("{}/{}", parent_str, uri);
+                        asset_fetch::fetch(
+                            loader,
+                            AssetPath::External(&full_uri),
+                            &mut report_progress,
+                        )
+                        .await
+                        .map_err(|e| format!("Failed to read gltf buffer {}: {}", full_uri, e))?
+                    }
+                },
+                gltf::buffer::Source::Bin => file.blob.clone().unwrap_or_default(),
+            });
+        }
+        let baked = morph::bake(&file, &mut buffers, morph_weights);
+        log::info!("Applied --morph-weight to {} meshes of {}", baked, path_str);
+        let mut baked_blob = None;
+        for (buffer, data) in file.buffers().zip(buffers) {
+            match buffer.source() {
+                gltf::buffer::Source::Uri(uri) => {
+                    baked_buffers.insert(uri.to_owned(), data);
+                }
+                gltf::buffer::Source::Bin => baked_blob = Some(data),
+            }
+        }
+        if baked_blob.is_some() {
+            file.blob = baked_blob;
+        }
+    }
+    // rend3-gltf doesn't keep the buffers around, so copies are held on to for the wireframe
+    // and depth of field.
+    let blob = file.blob.clone();
+    let buffer_uris: Vec<String> = file
+        .buffers()
+        .filter_map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Uri(uri) => Some(uri.to_owned()),
+            gltf::buffer::Source::Bin => None,
+        })
+        .collect();
+    let fetched_buffers = std::cell::RefCell::new(HashMap::<String, Vec<u8>>::new());
+    // The sizes of images are kept for the scene statistics, and the images themselves only to
+    // decode them again for --base-color-space linear.
+    let image_sizes = std::cell::RefCell::new(HashMap::<String, (u32, u32)>::new());
+    let fetched_images = std::cell::RefCell::new(HashMap::<String, Vec<u8>>::new());
+    let keep_images = color_space == color_space::ColorSpace::Linear;
+    let (parent_str, baked_buffers, buffer_uris, fetched_buffers, image_sizes, fetched_images) = (
+        &parent_str,
+        &baked_buffers,
+        &buffer_uris,
+        &fetched_buffers,
+        &image_sizes,
+        &fetched_images,
+    );
+    let scene = rend3_gltf::load_gltf_data(renderer, &mut file, settings, |uri| async move {
+        let data = if let Some(baked) = baked_buffers.get(uri.as_str()) {
+            Ok(baked.clone())
+        } else if let Some(base64) = rend3_gltf::try_load_base64(&uri) {
             Ok(base64)
         } else {
             log::info!("Loading resource {}", uri);
-            let uri = uri;
             let full_uri = parent_str.clone() + "/" + uri.as_str();
-            loader.get_asset(AssetPath::External(&full_uri)).await
+            let progress = progress.clone();
+            asset_fetch::fetch(loader, AssetPath::External(&full_uri), &mut |p| {
+                let _ = progress.send(LoadEvent::Download(p));
+            })
+            .await
+        };
+        if let Ok(ref data) = data {
+            if buffer_uris
+                .iter()
+                .any(|buffer_uri| buffer_uri == uri.as_str())
+            {
+                fetched_buffers
+                    .borrow_mut()
+                    .insert(uri.as_str().to_owned(), data.clone());
+            } else {
+                if let Some(size) = scene_stats::image_size(data) {
+                    image_sizes
+                        .borrow_mut()
+                        .insert(uri.as_str().to_owned(), size);
+                }
+                if keep_images {
+                    fetched_images
+                        .borrow_mut()
+                        .insert(uri.as_str().to_owned(), data.clone());
+                }
+            }
         }
+        data
     })
     .await
-    .unwrap();
+    .map_err(|e| format!("Failed to load gltf file {}: {}", path_str, e))?;
+    let mut fetched_buffers = fetched_buffers.take();
+    let buffers: Vec<Vec<u8>> = file
+        .buffers()
+        .map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Uri(uri) => fetched_buffers.remove(uri).unwrap_or_default(),
+            gltf::buffer::Source::Bin => blob.clone().unwrap_or_default(),
+        })
+        .collect();
+    // --center-model moves the middle of the scene's bounds to `center`, relative to
+    // `transform`.
+    let transform = match center {
+        Some(center) => {
+            let (positions, _) =
+                wireframe::collect_triangles(&file, &buffers, settings.scale, Mat4::IDENTITY);
+            match scene_stats::bounds(&positions) {
+                Some((min, max)) => transform * Mat4::from_translation(center - (min + max) * 0.5),
+                None => transform,
+            }
+        }
+        None => transform,
+    };
+    // What rend3_gltf::load_gltf does, but placing the scene with `transform`.
+    let nodes = file
+        .default_scene()
+        .or_else(|| file.scenes().next())
+        .map_or_else(Vec::new, |scene| scene.nodes().collect());
+    let instance = rend3_gltf::instance_loaded_scene::<asset_fetch::FetchError>(
+        renderer, &scene, nodes, settings, transform,
+    )
+    .map_err(|e| format!("Failed to instance gltf file {}: {}", path_str, e))?;
 
+    let resources_elapsed = resources_start.elapsed();
     log::info!(
         "Loaded gltf in {:.3?}, resources loaded in {:.3?}",
         gltf_elapsed,
-        resources_start.elapsed()
+        resources_elapsed
     );
-    Some((scene, instance))
+
+    let document = match scene_info::parse_document(&gltf_data) {
+        Ok(document) => Some(document),
+        Err(e) => {
+            log::warn!("Failed to parse gltf document of {}: {}", path_str, e);
+            None
+        }
+    };
+
+    if let Some(ref document) = document {
+        let morphs = scene_info::morph_targets(document);
+        for mesh in &morphs {
+            let targets: Vec<String> = mesh
+                .targets
+                .iter()
+                .zip(&mesh.default_weights)
+                .map(|(name, weight)| format!("{} ({:.2})", name, weight))
+                .collect();
+            log::info!("Mesh {} morph targets: {}", mesh.mesh, targets.join(", "));
+        }
+        if !morphs.is_empty() {
+            log::warn!(
+                "rend3 doesn't render morph targets, meshes using them show their base shape unless --morph-weight sets them"
+            );
+        }
+        if validate {
+            mesh_validation::MeshValidation::new(document, &buffers).log();
+        }
+        let mixed = color_space::mixed_images(document);
+        if !mixed.is_empty() {
+            log::warn!(
+                "Images {:?} are used both as color textures, decoded as sRGB, and as data textures, \
+                 decoded as linear, so some materials will look off. \
+                 If colors look washed out, try --base-color-space linear",
+                mixed
+            );
+        }
+    }
+
+    if let Some(avatar) = document.as_ref().and_then(vrm::parse) {
+        avatar.log();
+    }
+
+    let triangles = document.as_ref().map_or_else(Default::default, |document| {
+        wireframe::collect_triangles(document, &buffers, settings.scale, transform)
+    });
+    let stats = document.as_ref().map(|document| {
+        scene_stats::SceneStats::new(
+            &path_str,
+            document,
+            &buffers,
+            &image_sizes.borrow(),
+            &triangles.0,
+            &triangles.1,
+            gltf_elapsed,
+            resources_elapsed,
+        )
+    });
+
+    let color_textures = match (&document, color_space) {
+        (Some(document), color_space::ColorSpace::Linear) => {
+            color_space::upload_linear(renderer, document, &buffers, &fetched_images.borrow())
+        }
+        _ => HashMap::new(),
+    };
+
+    Ok(LoadedGltf {
+        path: path_str.into_owned(),
+        scene,
+        instance: Some(instance),
+        transform,
+        document,
+        triangles,
+        stats,
+        color_textures,
+    })
+}
+
+/// Loader for assets in `subdirectory` of the crate, or of `base_url` on the web.
+fn asset_loader(subdirectory: &str, base_url: &str) -> rend3_framework::AssetLoader {
+    rend3_framework::AssetLoader::new_local(
+        &format!("{}/{}", env!("CARGO_MANIFEST_DIR"), subdirectory),
+        "",
+        &format!("{}{}", base_url, subdirectory),
+    )
 }
 
 fn button_pressed<Hash: BuildHasher>(map: &HashMap<u32, bool, Hash>, key: u32) -> bool {
     map.get(&key).map_or(false, |b| *b)
 }
 
+/// Camera pitch and yaw that make the camera look along `direction`.
+///
+/// Inverse of the rotation built in [`SceneViewer::view_matrix`].
+fn pitch_yaw_towards(direction: Vec3) -> (f32, f32) {
+    let direction = direction.normalize();
+    let pitch = direction.y.clamp(-1.0, 1.0).asin().clamp(
+        -std::f32::consts::FRAC_PI_2 + 0.0001,
+        std::f32::consts::FRAC_PI_2 - 0.0001,
+    );
+    let yaw = (-direction.x)
+        .atan2(-direction.z)
+        .rem_euclid(std::f32::consts::TAU);
+    (pitch, yaw)
+}
+
 fn extract_backend(value: &str) -> Result<Backend, &'static str> {
     Ok(match value.to_lowercase().as_str() {
         "vulkan" | "vk" => Backend::Vulkan,
@@ -164,11 +846,29 @@ fn extract_profile(value: &str) -> Result<rend3::RendererProfile, &'static str>
     })
 }
 
+/// rend3 renders with either 1 or 4 samples, so those are the only levels accepted.
 fn extract_msaa(value: &str) -> Result<SampleCount, &'static str> {
     Ok(match value {
         "1" => SampleCount::One,
         "4" => SampleCount::Four,
-        _ => return Err("invalid msaa count"),
+        _ => return Err("msaa level must be 1 or 4"),
+    })
+}
+
+fn extract_handedness(value: &str) -> Result<Handedness, &'static str> {
+    Ok(match value.to_lowercase().as_str() {
+        "left" => Handedness::Left,
+        "right" => Handedness::Right,
+        _ => return Err("handedness must be left or right"),
+    })
+}
+
+#[cfg(feature = "gltf")]
+fn extract_color_space(value: &str) -> Result<color_space::ColorSpace, &'static str> {
+    Ok(match value.to_lowercase().as_str() {
+        "srgb" => color_space::ColorSpace::Srgb,
+        "linear" => color_space::ColorSpace::Linear,
+        _ => return Err("color space must be srgb or linear"),
     })
 }
 
@@ -176,8 +876,28 @@ fn extract_vsync(value: &str) -> Result<rend3::types::PresentMode, &'static str>
     Ok(match value.to_lowercase().as_str() {
         "immediate" => rend3::types::PresentMode::Immediate,
         "fifo" => rend3::types::PresentMode::Fifo,
+        "fifo_relaxed" => rend3::types::PresentMode::FifoRelaxed,
         "mailbox" => rend3::types::PresentMode::Mailbox,
-        _ => return Err("invalid msaa count"),
+        _ => return Err("invalid vsync mode"),
+    })
+}
+
+fn extract_alpha_mode(value: &str) -> Result<wgpu::CompositeAlphaMode, &'static str> {
+    Ok(match value.to_lowercase().as_str() {
+        "auto" => wgpu::CompositeAlphaMode::Auto,
+        "opaque" => wgpu::CompositeAlphaMode::Opaque,
+        "premultiplied" => wgpu::CompositeAlphaMode::PreMultiplied,
+        "postmultiplied" => wgpu::CompositeAlphaMode::PostMultiplied,
+        _ => return Err("alpha mode must be auto, opaque, premultiplied or postmultiplied"),
+    })
+}
+
+fn extract_power_saver(value: &str) -> Result<PowerSaver, &'static str> {
+    Ok(match value.to_lowercase().as_str() {
+        "off" => PowerSaver::Off,
+        "on" => PowerSaver::On,
+        "auto" => PowerSaver::Auto,
+        _ => return Err("power saver must be on, off or auto"),
     })
 }
 
@@ -197,6 +917,13 @@ fn extract_array<const N: usize>(value: &str, default: [f32; N]) -> Result<[f32;
     Ok(res)
 }
 
+fn extract_color(value: &str) -> Result<Vec4, &'static str> {
+    extract_array(value, [0.0; 4])
+        .map(Vec4::from_array)
+        .or_else(|_| extract_array(value, [0.0; 3]).map(|rgb| Vec3::from_array(rgb).extend(1.0)))
+        .map_err(|_| "Colors are defined with 3 or 4 values")
+}
+
 fn extract_vec3(value: &str) -> Result<Vec3, &'static str> {
     let mut res = [0.0_f32, 0.0, 0.0];
     let split: Vec<_> = value.split(',').enumerate().collect();
@@ -213,6 +940,287 @@ fn extract_vec3(value: &str) -> Result<Vec3, &'static str> {
     Ok(Vec3::from(res))
 }
 
+fn extract_list<T: FromStr>(value: &str) -> Result<Vec<T>, &'static str> {
+    value
+        .split(',')
+        .map(|inner| {
+            inner
+                .trim()
+                .parse()
+                .map_err(|_| "Cannot parse list element")
+        })
+        .collect()
+}
+
+fn extract_point_light(value: &str) -> Result<lights::PunctualLight, &'static str> {
+    lights::PunctualLight::parse_point(&extract_list(value)?)
+}
+
+fn extract_spot_light(value: &str) -> Result<lights::PunctualLight, &'static str> {
+    lights::PunctualLight::parse_spot(&extract_list(value)?)
+}
+
+#[cfg(feature = "gltf")]
+fn extract_morph_weight(value: &str) -> Result<morph::MorphWeight, &'static str> {
+    let (name, weight) = value
+        .split_once('=')
+        .ok_or("Morph weights are defined as target=weight or mesh/target=weight")?;
+    let weight = weight
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|weight| weight.is_finite())
+        .ok_or("Morph weight must be a number")?;
+    let (mesh, target) = match name.split_once('/') {
+        Some((mesh, target)) => (Some(mesh.trim().to_owned()), target.trim()),
+        None => (None, name.trim()),
+    };
+    if target.is_empty() {
+        return Err("Morph target name cannot be empty");
+    }
+    Ok(morph::MorphWeight {
+        mesh,
+        target: target.to_owned(),
+        weight,
+    })
+}
+
+#[cfg(feature = "puppet")]
+fn extract_puppet_param(value: &str) -> Result<(String, Vec2), &'static str> {
+    let (name, values) = value
+        .split_once('=')
+        .ok_or("Puppet parameters are defined as name=x,y or name=x")?;
+    if name.trim().is_empty() {
+        return Err("Puppet parameter name cannot be empty");
+    }
+    let values = extract_array(values, [0.0; 2])
+        .map(Vec2::from_array)
+        .or_else(|_| extract_array(values, [0.0; 1]).map(|[x]| vec2(x, 0.0)))
+        .map_err(|_| "Puppet parameters are defined as name=x,y or name=x")?;
+    Ok((name.trim().to_owned(), values))
+}
+
+/// Prints the parameters --puppet-param can set, with their ranges, for --list-puppet-params.
+#[cfg(feature = "puppet")]
+fn print_puppet_params(puppet: &inox2d::puppet::Puppet) {
+    let mut params: Vec<_> = puppet.params.values().collect();
+    params.sort_by(|a, b| a.name.cmp(&b.name));
+    for param in params {
+        if param.is_vec2 {
+            println!("{}: {} to {}", param.name, param.min, param.max);
+        } else {
+            println!("{}: {} to {}", param.name, param.min.x, param.max.x);
+        }
+    }
+}
+
+fn extract_binding(value: &str) -> Result<(key_bindings::Action, u32), &'static str> {
+    key_bindings::parse(value)
+}
+
+fn extract_time(value: &str) -> Result<f32, &'static str> {
+    let (hours, minutes) = value
+        .split_once(':')
+        .ok_or("Time must be formatted as HH:MM")?;
+    let hours: u32 = hours.trim().parse().map_err(|_| "Cannot parse hours")?;
+    let minutes: u32 = minutes.trim().parse().map_err(|_| "Cannot parse minutes")?;
+
+    if hours > 23 || minutes > 59 {
+        return Err("Time out of range");
+    }
+    Ok(hours as f32 + minutes as f32 / 60.0)
+}
+
+fn extract_date(value: &str) -> Result<u32, &'static str> {
+    let (month, day) = value
+        .split_once('-')
+        .ok_or("Date must be formatted as MM-DD")?;
+    let month: u32 = month.trim().parse().map_err(|_| "Cannot parse month")?;
+    let day: u32 = day.trim().parse().map_err(|_| "Cannot parse day")?;
+
+    sun::day_of_year(month, day).ok_or("Date out of range")
+}
+
+/// Flags which don't take a value, so their environment variable only turns them on or off.
+const SWITCHES: &[&str] = &[
+    "--help",
+    "--keep-alive",
+    "--quit-on-error",
+    "--alpha-to-coverage",
+    "--absolute-mouse",
+    "--no-grab",
+    "--fullscreen",
+    "--frame-graph",
+    "--wireframe",
+    "--low-latency",
+    "--auto-exposure",
+    "--light-gizmos",
+    "--shadow-bounds",
+    "--flat-background",
+    "--no-skybox",
+    "--linear-skybox",
+    "--normal-y-down",
+    "--gltf-disable-directional-lights",
+    "--use-gltf-camera",
+    "--orbit",
+    "--ortho",
+    "--dof-autofocus",
+    "--ibl-key-light",
+    "--coordinates",
+    "--auto-fit",
+    "--auto-frame",
+    "--center-model",
+    "--suggest-flags",
+    "--hide-puppet",
+    "--puppet-track-mouse",
+    "--puppet-watch",
+    "--invert-y",
+    "--smooth-camera",
+    "--list-puppet-params",
+    "--animation-once",
+    "--watch",
+    "--stats",
+    "--camera-stdin",
+    "--validate",
+];
+
+/// Short forms of long flags, so a short flag on the command line also overrides the environment.
+const SHORT_FLAGS: &[(&str, &str)] = &[
+    ("-h", "--help"),
+    ("-b", "--backend"),
+    ("-d", "--device"),
+    ("-p", "--profile"),
+    ("-v", "--vsync"),
+];
+
+/// Other names of long flags, which are renamed before parsing.
+const FLAG_ALIASES: &[(&str, &str)] = &[("--camera-fov", "--fov")];
+
+const ENV_PREFIX: &str = "SCENE_VIEWER_";
+
+/// The flag `name` stands for, which is itself unless it's one of [`FLAG_ALIASES`].
+fn canonical_flag(name: &str) -> &str {
+    FLAG_ALIASES
+        .iter()
+        .find(|&&(alias, _)| alias == name)
+        .map_or(name, |&(_, flag)| flag)
+}
+
+/// `arg` with an aliased flag name replaced by the flag it stands for, keeping any `=value`.
+fn canonical_arg(arg: OsString) -> OsString {
+    let renamed = {
+        let text = arg.to_string_lossy();
+        let (name, value) = match text.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (&*text, None),
+        };
+        let flag = canonical_flag(name);
+        (flag != name).then(|| match value {
+            Some(value) => OsString::from(format!("{}={}", flag, value)),
+            None => OsString::from(flag),
+        })
+    };
+    renamed.unwrap_or(arg)
+}
+
+/// Arguments for `flag` given as a name and value pair, like an environment variable or query
+/// parameter. Switches are only turned on by values that don't look like false.
+fn flag_args(flag: String, value: String) -> Vec<OsString> {
+    if SWITCHES.contains(&flag.as_str()) {
+        match value.to_lowercase().as_str() {
+            "0" | "false" | "no" | "off" => Vec::new(),
+            _ => vec![OsString::from(flag)],
+        }
+    } else {
+        vec![OsString::from(flag), OsString::from(value)]
+    }
+}
+
+/// The page's query parameters as arguments, since the web has no command line.
+/// `?msaa=4&fullscreen` acts like `--msaa 4 --fullscreen`.
+#[cfg(target_arch = "wasm32")]
+fn query_args() -> Vec<OsString> {
+    let Some(search) = web_sys::window().and_then(|window| window.location().search().ok()) else {
+        return Vec::new();
+    };
+    let decode = |text: &str| {
+        js_sys::decode_uri_component(&text.replace('+', " "))
+            .map_or_else(|_| text.to_owned(), String::from)
+    };
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .flat_map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            flag_args(format!("--{}", decode(name)), decode(value))
+        })
+        .collect()
+}
+
+/// Where assets are fetched from on the web when --asset-url isn't given.
+fn default_asset_url() -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            // Relative to the page, so a deployed build finds its assets wherever it's hosted.
+            web_sys::window()
+                .and_then(|window| window.location().href().ok())
+                .map(|href| {
+                    let page = href.split(['?', '#']).next().unwrap_or_default();
+                    page[..=page.rfind('/').unwrap_or(page.len() - 1)].to_owned()
+                })
+                .unwrap_or_else(|| "./".to_owned())
+        } else {
+            "http://localhost:8000/".to_owned()
+        }
+    }
+}
+
+/// Command line arguments, with `SCENE_VIEWER_*` environment variables filling in any flag
+/// that wasn't given on the command line. `SCENE_VIEWER_MSAA=4` acts like `--msaa 4`.
+fn parse_args() -> Arguments {
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    #[cfg(target_arch = "wasm32")]
+    args.extend(query_args());
+    args = args.into_iter().map(canonical_arg).collect();
+
+    let given = |flag: &str| {
+        let short = SHORT_FLAGS
+            .iter()
+            .find(|&&(_, long)| long == flag)
+            .map(|&(short, _)| short);
+        args.iter().any(|arg| {
+            let arg = arg.to_string_lossy();
+            let name = arg.split('=').next().unwrap_or_default();
+            name == flag || Some(name) == short
+        })
+    };
+
+    let mut env: Vec<(String, String)> = std::env::vars()
+        .filter(|(key, _)| key.starts_with(ENV_PREFIX))
+        .collect();
+    env.sort();
+
+    let mut from_env = Vec::new();
+    for (key, value) in env {
+        let flag = format!(
+            "--{}",
+            key[ENV_PREFIX.len()..].to_lowercase().replace('_', "-")
+        );
+        let flag = canonical_flag(&flag).to_owned();
+        if given(&flag) {
+            continue;
+        }
+        // An empty environment variable is as good as unset.
+        if !value.is_empty() {
+            from_env.extend(flag_args(flag, value));
+        }
+    }
+
+    args.extend(from_env);
+    Arguments::from_vec(args)
+}
+
 fn option_arg<T>(result: Result<Option<T>, pico_args::Error>) -> Option<T> {
     match result {
         Ok(o) => o,
@@ -231,6 +1239,26 @@ fn option_arg<T>(result: Result<Option<T>, pico_args::Error>) -> Option<T> {
     }
 }
 
+/// Slowest --walk and --run allowed, for positioning the camera in very small scenes.
+const MIN_MOVE_SPEED: f32 = 0.001;
+
+/// `speed` given for `flag`, clamped with a warning if it isn't a usable positive speed.
+/// Zero would leave the camera stuck and negative speeds reverse the controls.
+fn positive_speed(flag: &str, speed: f32) -> f32 {
+    if speed.is_finite() && speed >= MIN_MOVE_SPEED {
+        return speed;
+    }
+    eprintln!(
+        "{} must be a speed of at least {}, got {}. Using {} instead",
+        flag, MIN_MOVE_SPEED, speed, MIN_MOVE_SPEED
+    );
+    MIN_MOVE_SPEED
+}
+
+fn list_arg<T>(result: Result<Vec<T>, pico_args::Error>) -> Vec<T> {
+    option_arg(result.map(Some)).unwrap_or_default()
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn spawn<Fut>(fut: Fut)
 where
@@ -254,84 +1282,549 @@ where
 const HELP: &str = "\
 scene-viewer
 
-gltf and glb scene viewer powered by the rend3 rendering library.
+gltf, glb and vrm scene viewer powered by the rend3 rendering library.
+
+usage: scene-viewer --options ./path/to/gltf/file.gltf [more files...]
 
-usage: scene-viewer --options ./path/to/gltf/file.gltf
+.glb files and VRM avatars (.vrm) load the same way. With more than one file, the first one is shown and Tab switches
+to the next.
 
 Meta:
-  --help            This menu.
+  --help            This menu. Press F1 in the viewer for the list of runtime keys.
+  --keep-alive      Log errors after startup, like a file failing to load, and keep running. This is the default.
+  --quit-on-error   Exit with a non-zero code on errors after startup instead. Useful for scripts.
+
+Every flag can also be set with a SCENE_VIEWER_ environment variable, for example SCENE_VIEWER_MSAA=4 for --msaa 4
+or SCENE_VIEWER_FULLSCREEN=1 for --fullscreen. Flags given on the command line take precedence.
+Loading gltf files, the puppet and the skybox flags need the gltf, puppet and skybox cargo features, which are on by default.
 
 Rendering:
   -b --backend                 Choose backend to run on ('vk', 'dx12', 'dx11', 'metal', 'gl').
   -d --device                  Choose device to run on (case insensitive device substring).
   -p --profile                 Choose rendering profile to use ('cpu', 'gpu').
   -v --vsync                   Choose vsync mode ('immediate' [no-vsync], 'fifo' [vsync], 'fifo_relaxed' [adaptive vsync], 'mailbox' [fast vsync])
-  --msaa <level>               Level of antialiasing (either 1 or 4). Default 1.
+                               Cycle through the supported ones at runtime with V.
+                               Unsupported modes fall back to fifo.
+  --low-latency                Use the lowest latency present mode the surface supports: mailbox, then immediate, then fifo. Overrides --vsync.
+  --alpha-mode <mode>          How the window is composited with what is behind it: auto, opaque, premultiplied or postmultiplied.
+                               Defaults to auto, which lets the platform pick. Falls back to auto if the surface doesn't support it.
+  --handedness <left|right>    Coordinate system of the world, for content made for left handed engines. Defaults to right.
+                               Left handed worlds look down +Z where right handed ones look down -Z. Native only.
+  --msaa <level>               Level of antialiasing, 1 or 4. Default 1.
+  --target-fps <fps>           Continuously scale the internal render resolution to try to hit this framerate.
+  --max-fps <fps>              Render at most this many frames per second.
+  --power-saver <on|off|auto>  Render at most 30 frames per second at no more than 0.75 of the resolution. auto only does while
+                               running on battery, which is only detected on Linux. Defaults to off.
+  --crossfade <seconds>        Hold the previous frame while a scene loads, then fade it out over this many seconds.
+  --auto-exposure              Adjust exposure over time to keep the frame about equally bright in dark and bright scenes.
+  --exposure-speed <speed>     How quickly auto exposure adapts, higher is faster. Default 1.5.
 
 Windowing:
+  --asset-url <url>            Base url assets are fetched from when running on the web. Defaults to the directory of the page.
+                               On the web every flag can be given as a query parameter instead, like ?asset-url=https://example.com/.
   --absolute-mouse             Interpret the relative mouse coordinates as absolute. Useful when using things like VNC.
+  --no-grab                    Never grab the pointer, look around by dragging with the left mouse button instead. Toggle at runtime with G.
   --fullscreen                 Open the window in borderless fullscreen.
+  --record-input <path>        Record keyboard, mouse and resize input to a file.
+  --replay-input <path>        Replay input recorded with --record-input. Live keyboard and mouse input is ignored until it finishes.
+  --wireframe                  Draw the edges of the gltf's triangles over the shaded scene, in their rest pose. Toggle at runtime with F2.
+  --wireframe-color r,g,b,a    Color of the wireframe, its alpha blends it with the scene. Defaults to 0.05,0.05,0.05,0.8.
+  --wireframe-width <pixels>   How wide the wireframe's lines are. Defaults to 1.
+  --debug-line-width <pixels>  How wide debug lines, like the orbit pivot marker, are. Defaults to 2.
+  --dof-focus <distance>       Blur what is nearer or further than this distance, like a camera lens focused on it. Only gltf
+                               triangles in their rest pose block the view, other things get the blur of what is behind them.
+  --dof-aperture <percent>     Depth of field blur radius, in percent of the view height, for things infinitely far behind the focus.
+                               Defaults to 1.
+  --dof-autofocus              Keep the depth of field focused on what is in the middle of the view.
+  --frame-graph                Show a graph of recent frame times. Toggle at runtime with F3.
+  --coordinates                Show the world coordinates of the gltf surface under the cursor, or under the middle of the
+                               window while the pointer is grabbed. Toggle at runtime with F6.
+  --stats-interval <seconds>   How often frame time statistics are printed. Defaults to 1.
+  --percentiles <p,p,...>      Frame time percentiles to print with the statistics. Defaults to 95,99.
+  --bench <frames>             Render this many frames once the scene is loaded, print their statistics and exit. The pointer
+                               isn't grabbed, time advances by a fixed 1/60s per frame and the camera turns around the
+                               --look-at point or the point in front of it at --idle-orbit-speed, 0 to keep it still.
+  --light-gizmos               Show markers at every light. Toggle at runtime with L.
+  --shadow-bounds              Outline the box around the camera the --directional-light casts shadows in, which --shadow-distance
+                               sets the size of. Toggle at runtime with F4.
+  --flat-background            Start with the skybox hidden behind a flat background. Toggle at runtime with B.
+  --clear-color r,g,b[,a]      Color of the flat background, and of the sky while the skybox is missing or loading. Defaults to
+                               0,0,0,1.
+  --no-skybox                  Don't load a skybox, leaving the --clear-color behind the scene. B then has nothing to toggle.
+  --skybox <dir>               Load the skybox from right, left, top, bottom, front and back images in this directory, as jpg
+                               or png. All six need to be squares of the same size. Defaults to the bundled skybox.
+  --skybox-equirect <path>     Load the skybox from an equirectangular panorama (.hdr, .png, .jpg) instead, split into cube
+                               faces at load time. .hdr values above 1 are clipped.
+  --skybox-resolution <pixels> Size of the cube faces made from --skybox-equirect. Defaults to a quarter of the panorama width.
+  --linear-skybox              Treat the skybox images as linear data instead of sRGB encoded.
+  --skybox-faces <faces>       Which image to use for each cube face, as six of right,left,top,bottom,front,back in that order.
+                               For example back,front,top,bottom,right,left. Defaults to right,left,top,bottom,front,back.
+  --skybox-flip <face:axes,..> Flip cube faces horizontally (h), vertically (v) or both (hv), for example top:hv,bottom:v.
 
 Assets:
   --normal-y-down                        Interpret all normals as having the DirectX convention of Y down. Defaults to Y up.
-  --directional-light <x,y,z>            Create a directional light pointing towards the given coordinates.
+  --base-color-space <srgb|linear>       Decode gltf base color and emissive textures as this. Defaults to srgb, like the gltf spec says,
+                                         normal, metallic-roughness and occlusion textures are always linear. linear fixes files authored
+                                         with linear colors that look washed out.
+  --emissive-scale <scale>               Multiply the emissive of every gltf material by this, on top of its KHR_materials_emissive_strength.
+                                         Defaults to 1.
+  --validate                             Check the gltf meshes for degenerate and zero area triangles, non-manifold edges and missing
+                                         normals or uvs, and log what is found. Makes loading slower.
+  --morph-weight <[mesh/]target=w>       Set the gltf morph target with this name to this weight, on the given mesh or every mesh that
+                                         has one. Can be repeated. The morph targets are listed on load.
+  --directional-light <x,y,z>            Create a directional light pointing towards the given coordinates. Can be repeated.
   --directional-light-intensity <value>  All lights created by the above flag have this intensity. Defaults to 4.
+  --directional-light-color <r,g,b>      Linear color of the --directional-light and --sun-time lights. Defaults to 1,1,1.
+  --sun-time <HH:MM>                     Point the directional light from where the sun is at this local solar time. Overrides --directional-light.
+                                         Hold T/Y at runtime to move the sun backwards/forwards in time.
+  --sun-latitude <degrees>               Latitude used by --sun-time, positive is north. Defaults to 45.
+  --sun-date <MM-DD>                     Date used by --sun-time. Defaults to 06-21.
+  --daycycle <seconds>                   Move the sun through a whole day in this many seconds, changing its color and the ambient
+                                         light with it. Starts at --sun-time, or 06:00.
   --gltf-disable-directional-lights      Disable all directional lights in the gltf
+  --point-light <x,y,z[,r,g,b[,range]]>  Create a point light at the given position. Can be repeated.
+  --spot-light <x,y,z,dx,dy,dz,angle[,r,g,b[,range]]>
+                                         Create a spot light with a cone half angle in degrees. Can be repeated.
+  --point-light-intensity <value>        All lights created by the two flags above have this intensity. Defaults to 10.
   --ambient <value>                      Set the value of the minimum ambient light. This will be treated as white light of this intensity. Defaults to 0.1.
+  --benchmark-scene <count>              Add a grid of this many cubes to the scene as a deterministic stress test.
+  --ibl <path>                           Light the scene with an equirectangular environment map (.hdr, .png, .jpg). rend3 has no image based
+                                         lighting, so this is approximated by ambient light of the environment's average color.
+                                         --ambient scales it and defaults to 1 when this is set.
+  --ibl-key-light                        Add a directional light coming from the brightest spot of the --ibl environment, like the sun,
+                                         with its color and intensity, and take that light out of the ambient light.
+                                         --directional-light and --sun-time take precedence.
   --scale <scale>                        Scale all objects loaded by this factor. Defaults to 1.0.
   --shadow-distance <value>              Distance from the camera there will be directional shadows. Lower values means higher quality shadows. Defaults to 100.
   --shadow-resolution <value>            Resolution of the shadow map. Higher values mean higher quality shadows with high performance cost. Defaults to 2048.
 
 Controls:
   --walk <speed>               Walk speed (speed without holding shift) in units/second (typically meters). Default 10.
+                               Fractions like 0.05 work for small scenes, speeds below 0.001 are raised to it.
   --run  <speed>               Run speed (speed while holding shift) in units/second (typically meters). Default 50.
+  --mouse-sensitivity <value>  Multiplier for mouse look speed. Defaults to 1.0, or 0.25 with --absolute-mouse.
+  --invert-y                   Look down when moving the mouse or right stick up, like a flight stick.
+  --smooth-camera              Speed up and slow down smoothly when movement keys are pressed and released, for recording
+                               walkthroughs.
+  --camera-damping <rate>      How quickly --smooth-camera reaches the walk or run speed, per second. Higher is snappier.
+                               Defaults to 6.
+  --bind <action=scancode>     Move a movement key to another physical key, for layouts other than QWERTY. Actions are forward,
+                               back, left, right, up and run. Scancodes are decimal or hex like 0x1e, and are logged when a key is
+                               pressed. Can be repeated.
+  --gamepad-dead-zone <value>  How far gamepad sticks have to move before they do anything, from 0 to 1. Defaults to 0.15.
+                               With the gamepad feature the left stick moves, the right one looks around, pressing the left
+                               one runs and the triggers move down and up.
+  --pan-speed <value>          Multiplier for how far dragging with the middle mouse button moves the camera. Defaults to 1.0.
+                               While flying a pixel moves as far as walking for 2ms, in orbit mode it scales with the pivot distance.
   --camera x,y,z,pitch,yaw     Spawns the camera at the given position. Press Period to get the current camera position.
---puppet <path>                path to .inp
+  --fov <degrees>              Vertical field of view of the camera, from 10 to 120. Defaults to 60. Scroll while flying to change it.
+  --camera-fov <degrees>       Same as --fov.
+  --near <distance>            Distance to the camera's near plane, anything closer is cut off. Defaults to 0.1.
+  --ortho                      Start with an orthographic projection, without perspective. Toggle with F7, scroll to change the
+                               size of the view.
+  --look-at x,y,z              Start looking at this point instead of along the --camera pitch and yaw. Press K to look at it again.
+  --orbit                      Start in orbit mode: drag with the left mouse button to orbit around the --look-at point, or a point
+                               in front of the camera, the middle one to pan and the right one or the wheel to zoom. Toggle with O.
+                               The pivot is marked with a small cross of the x, y and z axes.
+  --idle-timeout <seconds>     For unattended displays: after this long without input, slowly orbit the camera around the
+                               --look-at point, the orbit pivot or the point in front of the camera until there is input again.
+  --idle-orbit-speed <degrees> How many degrees per second the idle orbit turns, negative to turn the other way. Defaults to 10.
+  --camera-transition <seconds> Ease the camera over this many seconds when it jumps to a gltf camera, the --look-at point or
+                               an out of view scene, instead of snapping. Defaults to 0.
+  --use-gltf-camera            Start from the first camera in the gltf file, if there is one. Press N at runtime to cycle cameras.
+  --auto-fit                   If the loaded gltf scene is out of view, move the camera back from it until all of it is seen.
+                               Otherwise a warning is logged with where the scene is.
+  --auto-frame                 Always move the camera to fit the loaded gltf scene into view, looking along the --camera pitch and
+                               yaw. Press F at runtime to frame the shown scene again.
+  --center-model               Move the loaded gltf so the middle of its bounding box is at the --look-at point, or the origin,
+                               so orbiting turns around the model instead of its pivot.
+  --animation <name>           Play the gltf animation with this name or index on a loop. The available ones are listed on load.
+                               Press Space to pause and resume it.
+  --animation-once             Play --animation once and stop on its last frame instead of looping.
+  --blend-animation <name>     Cross-blend a second animation into --animation, like a run into a walk. Hold Minus/Equal to change the mix.
+  --animation-blend <weight>   How much of --blend-animation is mixed in, from 0 to 1. Defaults to 0.5.
+  --compare <path>             Load a second gltf file and show it next to the first one, seen from the same camera.
+                               Press C at runtime to switch between the split, a wipe and only the first file.
+                               Drag the wipe line with the left mouse button while the pointer isn't grabbed.
+  --watch                      Load the gltf file again whenever it changes on disk, like after exporting it again. Native only.
+  --camera-log <path>          Write the camera position, rotation and matrices of every frame to a csv file.
+  --camera-stdin               Read cameras from stdin, one x,y,z,pitch,yaw per line like --camera, and save a png of each view,
+                               then exit when stdin is closed. A path after the camera, separated by a space, says where to save it.
+                               Rendering starts once the scene is loaded. Native only.
+  --camera-stdin-dir <dir>     Where --camera-stdin saves views without a path, numbered from frame-0000.png. Defaults to the
+                               current directory.
+  --stats                      Once a gltf file is loaded, log its mesh, vertex, triangle, material and texture counts, the sizes of
+                               its images and its bounding box.
+  --stats-json <path>          Once the gltf file is loaded, write its mesh, material and texture counts, estimated VRAM use,
+                               bounding box and load times to a json file.
+  --suggest-flags              Once the gltf file is loaded, print its bounding box and a command line with a --camera, --look-at,
+                               --near and if needed --scale that frame it from the current camera direction.
+  --puppet <path>              Animate an inochi2d puppet from this .inp file. No puppet is loaded without it.
+  --hide-puppet                Load the --puppet but don't draw it. Toggle at runtime with H.
+  --puppet-scale <value>       Scale of the --puppet. Change at runtime with ; and '. Defaults to 0.12.
+  --puppet-position <x,y>      Position of the puppet camera, in puppet units. Defaults to 0,0.
+  --puppet-track-mouse         Turn the puppet's head towards the cursor instead of swaying it.
+  --puppet-watch               Load the --puppet again whenever its .inp file changes on disk. Native only.
+  --list-puppet-params         Print the names and ranges of the --puppet's parameters, then exit.
+  --puppet-param <name=x[,y]>  Hold a puppet parameter at this value, overriding the head movement for its own parameter. The
+                               names depend on the .inp file. Can be repeated.
 ";
 
-struct SceneViewer {
-    absolute_mouse: bool,
-    desired_backend: Option<Backend>,
+/// State of a --bench run.
+struct Bench {
+    /// Frames to measure.
+    frames: u32,
+    /// When the scene finished loading and measuring started, `None` before.
+    start: Option<Instant>,
+    times: histogram::Histogram,
+}
+
+/// Everything the runtime keys change, kept as parsed from the command line so F5 can go back
+/// to it.
+#[derive(Debug, Clone)]
+struct Tweaks {
+    camera_location: Vec3A,
+    camera_pitch: f32,
+    camera_yaw: f32,
+    camera_fov: f32,
+    projection_mode: ProjectionMode,
+    ortho_height: f32,
+    orbit_mode: bool,
+    sun: Option<sun::SunPosition>,
+    ambient_light_level: f32,
+    show_skybox: bool,
+    show_light_gizmos: bool,
+    show_shadow_bounds: bool,
+    show_frame_graph: bool,
+    #[cfg(feature = "gltf")]
+    show_wireframe: bool,
+    #[cfg(feature = "gltf")]
+    show_coordinates: bool,
+    #[cfg(feature = "gltf")]
+    material_adjustment: material_override::MaterialAdjustment,
+    #[cfg(feature = "gltf")]
+    animation_blend: f32,
+    #[cfg(feature = "gltf")]
+    animation_paused: bool,
+    #[cfg(feature = "gltf")]
+    compare_mode: CompareMode,
+    #[cfg(feature = "puppet")]
+    puppet_enabled: bool,
+    #[cfg(feature = "puppet")]
+    puppet_scale: f32,
+}
+
+struct SceneViewer {
+    error_policy: ErrorPolicy,
+    absolute_mouse: bool,
+    grab_enabled: bool,
+    #[cfg(any(feature = "gltf", feature = "skybox"))]
+    asset_url: String,
+    desired_backend: Option<Backend>,
     desired_device_name: Option<String>,
     desired_profile: Option<RendererProfile>,
-    file_to_load: Option<String>,
+    #[cfg(feature = "gltf")]
+    files_to_load: Vec<String>,
+    #[cfg(feature = "gltf")]
+    compare_file: Option<String>,
     walk_speed: f32,
     run_speed: f32,
+    mouse_sensitivity: f64,
+    invert_y: bool,
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<gamepad::Gamepad>,
+    /// Multiplier for how far dragging with the middle mouse button pans.
+    pan_speed: f32,
+    #[cfg(feature = "gltf")]
     gltf_settings: rend3_gltf::GltfLoadSettings,
-    directional_light_direction: Option<Vec3>,
+    /// What base color and emissive textures are decoded as, from --base-color-space.
+    #[cfg(feature = "gltf")]
+    base_color_space: color_space::ColorSpace,
+    /// Check the loaded meshes for bad geometry, from --validate.
+    #[cfg(feature = "gltf")]
+    validate: bool,
+    /// Morph target weights baked into the loaded meshes, from --morph-weight.
+    #[cfg(feature = "gltf")]
+    morph_weights: Vec<morph::MorphWeight>,
+    /// Multiplier of every material's emissive, from --emissive-scale.
+    #[cfg(feature = "gltf")]
+    emissive_scale: f32,
+    shadow_distance: f32,
+    directional_light_directions: Vec<Vec3>,
     directional_light_intensity: f32,
-    directional_light: Option<DirectionalLightHandle>,
+    directional_light_color: Vec3,
+    directional_lights: Vec<DirectionalLightHandle>,
+    sun: Option<sun::SunPosition>,
+    /// Seconds a whole day of --daycycle takes.
+    daycycle: Option<f32>,
+    punctual_lights: Vec<lights::PunctualLight>,
+    punctual_light_intensity: f32,
+    punctual_light_handles: Vec<PointLightHandle>,
+    show_light_gizmos: bool,
+    /// Outline the shadow map volume of the directional light, from --shadow-bounds.
+    show_shadow_bounds: bool,
+    light_gizmos: Option<gizmos::LightGizmos>,
     ambient_light_level: f32,
+    ambient_color: Vec3,
+    benchmark_scene_count: Option<u32>,
+    benchmark_objects: Vec<rend3::types::ObjectHandle>,
     present_mode: rend3::types::PresentMode,
+    /// Pick the first supported of [`LOW_LATENCY_PRESENT_MODES`] instead of `present_mode`.
+    low_latency: bool,
+    /// Present modes of the window surface, which V cycles through. Empty without a surface.
+    supported_present_modes: Vec<wgpu::PresentMode>,
+    alpha_mode: wgpu::CompositeAlphaMode,
+    /// What the window surface's textures can be used for, see
+    /// [`SceneViewer::negotiate_surface_usage`].
+    surface_usage: wgpu::TextureUsages,
+    /// Coordinate system of the renderer and the camera.
+    handedness: Handedness,
+    /// Sample count asked for with --msaa, `samples` is what is actually used.
+    msaa: SampleCount,
     samples: SampleCount,
+    render_scale: f32,
+    /// Shortest time between frames from --max-fps.
+    min_frame_time: Option<Duration>,
+    /// The earliest the next frame may start, with a frame limit.
+    next_frame: Instant,
+    power_saver: PowerSaver,
+    /// Whether frames are currently limited for saving power.
+    saving_power: bool,
+    last_power_check: Option<Instant>,
+    target_frame_time: Option<Duration>,
+    /// Adaptation speed, if auto exposure is on.
+    auto_exposure_speed: Option<f32>,
+    auto_exposure: Option<auto_exposure::AutoExposure>,
+    show_skybox: bool,
+    /// What the frame is cleared to, seen wherever there is no geometry or skybox. From
+    /// --clear-color.
+    clear_color: Vec4,
+    /// `None` with --no-skybox, which doesn't load one at all.
+    #[cfg(feature = "skybox")]
+    skybox_layout: Option<skybox::SkyboxLayout>,
 
     fullscreen: bool,
 
     scancode_status: FastHashMap<u32, bool>,
+    /// How fast --smooth-camera eases towards the speed of the held keys, per second.
+    camera_damping: Option<f32>,
+    camera_velocity: Vec3A,
+    /// Scancodes of the movement keys, from --bind.
+    key_bindings: FastHashMap<key_bindings::Action, u32>,
     camera_pitch: f32,
     camera_yaw: f32,
     camera_location: Vec3A,
+    /// Vertical field of view in degrees, from --fov.
+    camera_fov: f32,
+    /// Degrees the field of view still has to change by from zooming with the wheel, applied
+    /// over the next frames so it doesn't jump.
+    fov_zoom: f32,
+    /// Distance to the near plane, from --near. There is no far plane.
+    camera_near: f32,
+    projection_mode: ProjectionMode,
+    /// Height of the view in orthographic mode, in world units. The width follows the aspect
+    /// ratio.
+    ortho_height: f32,
+    #[cfg(feature = "gltf")]
+    use_gltf_camera: bool,
+    /// Cameras of the loaded gltf, which N cycles through.
+    #[cfg(feature = "gltf")]
+    scene_cameras: Vec<scene_info::SceneCamera>,
+    /// Index into `scene_cameras` of the camera in use, `None` for the free camera.
+    #[cfg(feature = "gltf")]
+    scene_camera: Option<usize>,
+    /// Location, pitch, yaw and fov of the free camera, to go back to after the gltf cameras.
+    #[cfg(feature = "gltf")]
+    free_camera: CameraPose,
+    /// Move the camera to a loaded scene that is out of view, from --auto-fit.
+    #[cfg(feature = "gltf")]
+    auto_fit: bool,
+    /// Frame every loaded scene, whether it is in view or not, from --auto-frame.
+    #[cfg(feature = "gltf")]
+    auto_frame: bool,
+    /// Move loaded scenes so their middle is at the --look-at point or the origin, from
+    /// --center-model.
+    #[cfg(feature = "gltf")]
+    center_model: bool,
+    #[cfg(feature = "gltf")]
+    animation_name: Option<String>,
+    #[cfg(feature = "gltf")]
+    blend_animation_name: Option<String>,
+    #[cfg(feature = "gltf")]
+    animation_blend: f32,
+    #[cfg(feature = "gltf")]
+    animation: Option<animation::AnimationPlayer>,
+    /// Stop at the end of the animation, from --animation-once.
+    #[cfg(feature = "gltf")]
+    animation_once: bool,
+    /// Holds the animation still while the rest of time runs, toggled with Space.
+    #[cfg(feature = "gltf")]
+    animation_paused: bool,
+    camera_log_path: Option<String>,
+    /// Views to render and save from --camera-stdin.
+    #[cfg(not(target_arch = "wasm32"))]
+    camera_script: Option<camera_script::CameraScript>,
+    /// Where to save the next frame, for --camera-stdin and F12.
+    #[cfg(not(target_arch = "wasm32"))]
+    frame_capture: Option<std::path::PathBuf>,
+    /// Number of the next F12 capture.
+    #[cfg(not(target_arch = "wasm32"))]
+    capture_count: u32,
+    /// Let time run again once the F12 capture is saved, if it wasn't frozen before.
+    #[cfg(not(target_arch = "wasm32"))]
+    resume_after_capture: bool,
+    /// Log the statistics of every loaded gltf, from --stats.
+    #[cfg(feature = "gltf")]
+    log_stats: bool,
+    /// Where --stats-json writes the statistics of the loaded gltf.
+    #[cfg(feature = "gltf")]
+    stats_json_path: Option<String>,
+    /// Print the scene's bounds and flags to frame it once it loads, from --suggest-flags.
+    #[cfg(feature = "gltf")]
+    suggest_flags: bool,
+    /// Point from --look-at that K turns the camera towards.
+    look_at_target: Option<Vec3>,
+    /// Drag to orbit, pan and zoom around `orbit_target` with a visible cursor instead of flying.
+    orbit_mode: bool,
+    orbit_target: Vec3,
+    /// Distance the orbit keeps the camera from `orbit_target`, changed by zooming.
+    orbit_radius: f32,
+    camera_log: Option<camera_log::CameraLog>,
     previous_profiling_stats: Option<Vec<GpuTimerScopeResult>>,
+    gpu_timings: gpu_timings::GpuTimingSummary,
     timestamp_last_second: Instant,
+    stats_interval: Duration,
+    percentiles: Vec<f64>,
     timestamp_last_frame: Instant,
-    timestamp_start: Instant,
+    /// Seconds of time-driven state, like the puppet and animations, which stops while frozen.
+    scene_time: f32,
+    /// The scene time everything is held at by F9 and F12, `None` while time runs.
+    frozen_time: Option<f32>,
     frame_times: histogram::Histogram,
+    bench: Option<Bench>,
+    frame_graph: frame_graph::FrameTimeGraph,
+    show_frame_graph: bool,
+    show_key_help: bool,
+    #[cfg(feature = "gltf")]
+    show_wireframe: bool,
+    #[cfg(feature = "gltf")]
+    wireframe_color: Vec4,
+    /// In pixels, like `debug_line_width`.
+    #[cfg(feature = "gltf")]
+    wireframe_width: f32,
+    debug_line_width: f32,
+    #[cfg(feature = "gltf")]
+    wireframe: Option<wireframe::Wireframe>,
+    #[cfg(feature = "gltf")]
+    show_coordinates: bool,
+    /// Where the coordinate readout last ray cast from: the cursor and the camera location,
+    /// pitch, yaw, field of view and orthographic height. It is only redone when one of them
+    /// changes.
+    #[cfg(feature = "gltf")]
+    last_pick: Option<(Vec2, Vec3A, f32, f32, f32, f32)>,
+    /// The surface point under the cursor, relative to the scene it is in.
+    #[cfg(feature = "gltf")]
+    cursor_hit: Option<Vec3>,
+    /// Rebuilt every frame, see [`SceneViewer::build_debug_lines`].
+    debug_lines: Option<debug_lines::DebugLines>,
+    /// Focus distance, aperture and autofocus, if depth of field is on.
+    #[cfg(feature = "gltf")]
+    depth_of_field_settings: Option<(f32, f32, bool)>,
+    #[cfg(feature = "gltf")]
+    depth_of_field: Option<depth_of_field::DepthOfField>,
     last_mouse_delta: Option<DVec2>,
+    left_mouse_down: bool,
+    middle_mouse_down: bool,
+    right_mouse_down: bool,
+    cursor_position: Option<DVec2>,
+    /// How long without input before the idle orbit starts, from --idle-timeout.
+    idle_timeout: Option<Duration>,
+    /// Radians per second.
+    idle_orbit_speed: f32,
+    last_input: Instant,
+    idle: bool,
+    /// Seconds camera jumps are eased over, from --camera-transition. Zero snaps.
+    camera_transition_time: f32,
+    camera_transition: Option<CameraTransition>,
+    record_input_path: Option<String>,
+    replay_input_path: Option<String>,
+    input_recorder: Option<input_record::InputRecorder>,
+    input_replay: Option<input_record::InputReplay>,
+
+    load_events: Option<mpsc::Receiver<LoadEvent>>,
+    /// Set from spawning the loading task until it reports back that it finished.
+    loading: bool,
+    /// Bytes downloaded so far and the total expected, for the loading screen.
+    download_progress: (u64, u64),
+    /// Kept alive here, dropping it would remove the scene from the renderer.
+    #[cfg(feature = "gltf")]
+    scene: Option<LoadedGltf>,
+    /// Second scene from --compare, loaded at [`COMPARE_OFFSET`].
+    #[cfg(feature = "gltf")]
+    compare_scene: Option<LoadedGltf>,
+    /// The hidden scenes of the files after the first, in the order Tab switches to them.
+    #[cfg(feature = "gltf")]
+    other_scenes: VecDeque<LoadedGltf>,
+    /// The first gltf file and its watch, with --watch.
+    #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+    file_watch: Option<(String, file_watch::FileWatch)>,
+    #[cfg(feature = "gltf")]
+    material_adjustment: material_override::MaterialAdjustment,
+    /// How the frame is shared between the scene and the compared scene.
+    #[cfg(feature = "gltf")]
+    compare_mode: CompareMode,
+    /// Position of the wipe line as a fraction of the window width.
+    wipe_position: f32,
+    /// Set while the wipe line is being dragged with the left mouse button.
+    dragging_wipe: bool,
 
     grabber: Option<rend3_framework::Grabber>,
-    inox_model: inox2d::model::Model,
+    #[cfg(feature = "puppet")]
+    inox_model: Option<inox2d::model::Model>,
+    #[cfg(feature = "puppet")]
     inox_renderer: Option<inox2d_wgpu::Renderer>,
+    #[cfg(feature = "puppet")]
     inox_texture: Option<wgpu::Texture>,
+    /// The --puppet file and its watch, with --puppet-watch.
+    #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+    puppet_watch: Option<(String, file_watch::FileWatch)>,
+    /// Disabled puppets are neither animated nor rendered, but stay loaded.
+    #[cfg(feature = "puppet")]
+    puppet_enabled: bool,
+    #[cfg(feature = "puppet")]
+    puppet_scale: f32,
+    #[cfg(feature = "puppet")]
+    puppet_position: Vec2,
+    #[cfg(feature = "puppet")]
+    puppet_track_mouse: bool,
+    /// Parameters set on the puppet every frame, after the head.
+    #[cfg(feature = "puppet")]
+    puppet_params: Vec<(String, Vec2)>,
+    overlay: Option<overlay::Overlay>,
+    crossfade_duration: Option<Duration>,
+    crossfade: Option<crossfade::CrossFade>,
+    /// The tweaks as they were at startup, which F5 resets to.
+    startup_tweaks: Option<Tweaks>,
 }
 impl SceneViewer {
     pub fn new() -> Self {
         #[cfg(feature = "tracy")]
         tracy_client::Client::start();
-        let timestamp_start = Instant::now();
-        let mut args = Arguments::from_vec(std::env::args_os().skip(1).collect());
+        let mut args = parse_args();
 
         // Meta
         let help = args.contains(["-h", "--help"]);
+        let keep_alive = args.contains("--keep-alive");
+        let quit_on_error = args.contains("--quit-on-error");
+        if keep_alive && quit_on_error {
+            eprintln!("--keep-alive and --quit-on-error can't be used together");
+            std::process::exit(1);
+        }
+        let error_policy = if quit_on_error {
+            ErrorPolicy::Quit
+        } else {
+            ErrorPolicy::KeepAlive
+        };
 
         // Rendering
         let desired_backend =
@@ -340,37 +1833,342 @@ impl SceneViewer {
             option_arg(args.opt_value_from_str(["-d", "--device"]))
                 .map(|s: String| s.to_lowercase());
         let desired_mode = option_arg(args.opt_value_from_fn(["-p", "--profile"], extract_profile));
-        let samples =
+        let msaa =
             option_arg(args.opt_value_from_fn("--msaa", extract_msaa)).unwrap_or(SampleCount::One);
+        // Narrowed down to what the adapter supports once there is one.
+        let samples = msaa;
         let present_mode = option_arg(args.opt_value_from_fn(["-v", "--vsync"], extract_vsync))
             .unwrap_or(rend3::types::PresentMode::Immediate);
+        let low_latency = args.contains("--low-latency");
+        let alpha_mode = option_arg(args.opt_value_from_fn("--alpha-mode", extract_alpha_mode))
+            .unwrap_or(wgpu::CompositeAlphaMode::Auto);
+        let handedness = option_arg(args.opt_value_from_fn("--handedness", extract_handedness))
+            .unwrap_or(Handedness::Right);
+        // rend3-framework creates the renderer on the web, with the fixed App::HANDEDNESS.
+        #[cfg(target_arch = "wasm32")]
+        let handedness = if matches!(handedness, Handedness::Left) {
+            warn!("--handedness left is only supported natively, using right");
+            Handedness::Right
+        } else {
+            handedness
+        };
+        if args.contains("--alpha-to-coverage") {
+            // The forward pipelines are built inside of rend3-routine with a fixed multisample
+            // state, which the viewer has no way to change.
+            eprintln!("--alpha-to-coverage is not supported, the rend3 pbr routine doesn't expose its multisample state");
+            std::process::exit(1);
+        }
+        let target_fps: Option<f32> = option_arg(args.opt_value_from_str("--target-fps"));
+        if let Some(fps) = target_fps {
+            if fps <= 0.0 || !fps.is_finite() {
+                eprintln!("--target-fps must be a positive number");
+                std::process::exit(1);
+            }
+        }
+        let max_fps: Option<f32> = option_arg(args.opt_value_from_str("--max-fps"));
+        if let Some(fps) = max_fps {
+            if fps <= 0.0 || !fps.is_finite() {
+                eprintln!("--max-fps must be a positive number");
+                std::process::exit(1);
+            }
+        }
+        let power_saver = option_arg(args.opt_value_from_fn("--power-saver", extract_power_saver))
+            .unwrap_or(PowerSaver::Off);
+        if power_saver == PowerSaver::Auto && power::on_battery().is_none() {
+            warn!("Can't tell whether this machine is on battery, --power-saver auto stays off");
+        }
+        let crossfade: Option<f32> = option_arg(args.opt_value_from_str("--crossfade"));
+        if let Some(seconds) = crossfade {
+            if seconds < 0.0 || !seconds.is_finite() {
+                eprintln!("--crossfade must be zero or a positive number of seconds");
+                std::process::exit(1);
+            }
+        }
+        // Fading over no time at all is the same as not fading.
+        let crossfade_duration = crossfade
+            .filter(|&seconds| seconds > 0.0)
+            .map(Duration::from_secs_f32);
+        let exposure_speed: f32 =
+            option_arg(args.opt_value_from_str("--exposure-speed")).unwrap_or(1.5);
+        if exposure_speed <= 0.0 || !exposure_speed.is_finite() {
+            eprintln!("--exposure-speed must be a positive number");
+            std::process::exit(1);
+        }
+        let auto_exposure_speed = args.contains("--auto-exposure").then_some(exposure_speed);
 
         // Windowing
         let absolute_mouse: bool = args.contains("--absolute-mouse");
+        let no_grab = args.contains("--no-grab");
         let fullscreen = args.contains("--fullscreen");
-        let puppet =
-            option_arg(args.opt_value_from_str("--puppet")).unwrap_or("Midori.inp".to_owned());
+        let mut asset_url: String =
+            option_arg(args.opt_value_from_str("--asset-url")).unwrap_or_else(default_asset_url);
+        if !asset_url.ends_with('/') {
+            asset_url.push('/');
+        }
+        let record_input_path: Option<String> =
+            option_arg(args.opt_value_from_str("--record-input"));
+        let replay_input_path: Option<String> =
+            option_arg(args.opt_value_from_str("--replay-input"));
+        let show_frame_graph = args.contains("--frame-graph");
+        #[cfg(feature = "gltf")]
+        let show_wireframe = args.contains("--wireframe");
+        #[cfg(feature = "gltf")]
+        let wireframe_color = option_arg(args.opt_value_from_fn("--wireframe-color", |value| {
+            extract_array(value, [0.0; 4]).map(Vec4::from_array)
+        }))
+        .unwrap_or(wireframe::DEFAULT_COLOR);
+        #[cfg(feature = "gltf")]
+        let wireframe_width: f32 =
+            option_arg(args.opt_value_from_str("--wireframe-width")).unwrap_or(1.0);
+        let debug_line_width: f32 =
+            option_arg(args.opt_value_from_str("--debug-line-width")).unwrap_or(2.0);
+        #[cfg(feature = "gltf")]
+        let line_widths = [
+            ("--wireframe-width", wireframe_width),
+            ("--debug-line-width", debug_line_width),
+        ];
+        #[cfg(not(feature = "gltf"))]
+        let line_widths = [("--debug-line-width", debug_line_width)];
+        for (flag, width) in line_widths {
+            if width <= 0.0 || !width.is_finite() {
+                eprintln!("{} must be a positive number of pixels", flag);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "gltf")]
+        let show_coordinates = args.contains("--coordinates");
+        #[cfg(feature = "gltf")]
+        let depth_of_field_settings = {
+            let focus: Option<f32> = option_arg(args.opt_value_from_str("--dof-focus"));
+            let aperture: f32 =
+                option_arg(args.opt_value_from_str("--dof-aperture")).unwrap_or(1.0);
+            let autofocus = args.contains("--dof-autofocus");
+            if focus.map_or(false, |focus| focus <= 0.0 || !focus.is_finite()) {
+                eprintln!("--dof-focus must be a positive distance");
+                std::process::exit(1);
+            }
+            if aperture < 0.0 || !aperture.is_finite() {
+                eprintln!("--dof-aperture must not be negative");
+                std::process::exit(1);
+            }
+            (focus.is_some() || autofocus)
+                .then(|| (focus.unwrap_or(ORBIT_DEFAULT_DISTANCE), aperture, autofocus))
+        };
+        let stats_interval: f32 =
+            option_arg(args.opt_value_from_str("--stats-interval")).unwrap_or(1.0);
+        if stats_interval <= 0.0 || !stats_interval.is_finite() {
+            eprintln!("--stats-interval must be a positive number of seconds");
+            std::process::exit(1);
+        }
+        let mut percentiles: Vec<f64> =
+            option_arg(args.opt_value_from_fn("--percentiles", extract_list))
+                .unwrap_or_else(|| vec![95.0, 99.0]);
+        if percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) {
+            eprintln!("--percentiles must all be between 0 and 100");
+            std::process::exit(1);
+        }
+        percentiles.sort_by(f64::total_cmp);
+        let bench = option_arg(args.opt_value_from_str("--bench")).map(|frames: u32| {
+            if frames == 0 {
+                eprintln!("--bench must render at least one frame");
+                std::process::exit(1);
+            }
+            Bench {
+                frames,
+                start: None,
+                times: histogram::Histogram::new(),
+            }
+        });
+        // Benchmarks run unattended, without taking over the pointer.
+        let grab_enabled = !no_grab && bench.is_none();
+        let show_light_gizmos = args.contains("--light-gizmos");
+        let show_shadow_bounds = args.contains("--shadow-bounds");
+        let show_skybox = !args.contains("--flat-background");
+        let clear_color = option_arg(args.opt_value_from_fn("--clear-color", extract_color))
+            .unwrap_or(Vec4::new(0.0, 0.0, 0.0, 1.0));
+        #[cfg(feature = "skybox")]
+        let skybox_layout = (!args.contains("--no-skybox")).then(|| skybox::SkyboxLayout {
+            directory: option_arg(args.opt_value_from_str("--skybox")),
+            equirect: option_arg(args.opt_value_from_str("--skybox-equirect")),
+            resolution: option_arg(args.opt_value_from_str("--skybox-resolution")),
+            sources: option_arg(args.opt_value_from_fn("--skybox-faces", skybox::parse_sources))
+                .unwrap_or(skybox::SkyboxLayout::default().sources),
+            flips: option_arg(args.opt_value_from_fn("--skybox-flip", skybox::parse_flips))
+                .unwrap_or_default(),
+            srgb: !args.contains("--linear-skybox"),
+        });
+        #[cfg(feature = "puppet")]
+        let puppet: Option<String> = option_arg(args.opt_value_from_str("--puppet"));
+        #[cfg(feature = "puppet")]
+        let puppet_enabled = !args.contains("--hide-puppet");
+        #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+        let watch_puppet = args.contains("--puppet-watch");
+        #[cfg(feature = "puppet")]
+        let list_puppet_params = args.contains("--list-puppet-params");
+        #[cfg(feature = "puppet")]
+        if list_puppet_params && puppet.is_none() {
+            eprintln!("--list-puppet-params needs a --puppet");
+            std::process::exit(1);
+        }
+        #[cfg(feature = "puppet")]
+        let puppet_scale: f32 =
+            option_arg(args.opt_value_from_str("--puppet-scale")).unwrap_or(PUPPET_SCALE);
+        #[cfg(feature = "puppet")]
+        if puppet_scale <= 0.0 || !puppet_scale.is_finite() {
+            eprintln!("--puppet-scale must be positive");
+            std::process::exit(1);
+        }
+        #[cfg(feature = "puppet")]
+        let puppet_position: Vec2 =
+            option_arg(args.opt_value_from_fn("--puppet-position", |value| {
+                extract_array(value, [0.0; 2]).map(Vec2::from_array)
+            }))
+            .unwrap_or(Vec2::ZERO);
+        #[cfg(feature = "puppet")]
+        let puppet_track_mouse = args.contains("--puppet-track-mouse");
+        #[cfg(feature = "puppet")]
+        let puppet_params = list_arg(args.values_from_fn("--puppet-param", extract_puppet_param));
         // Assets
+        #[cfg(feature = "gltf")]
         let normal_direction = match args.contains("--normal-y-down") {
             true => NormalTextureYDirection::Down,
             false => NormalTextureYDirection::Up,
         };
-        let directional_light_direction =
-            option_arg(args.opt_value_from_fn("--directional-light", extract_vec3));
+        #[cfg(feature = "gltf")]
+        let base_color_space =
+            option_arg(args.opt_value_from_fn("--base-color-space", extract_color_space))
+                .unwrap_or(color_space::ColorSpace::Srgb);
+        #[cfg(feature = "gltf")]
+        let validate = args.contains("--validate");
+        #[cfg(feature = "gltf")]
+        let morph_weights = list_arg(args.values_from_fn("--morph-weight", extract_morph_weight));
+        #[cfg(feature = "gltf")]
+        let emissive_scale: f32 =
+            option_arg(args.opt_value_from_str("--emissive-scale")).unwrap_or(1.0);
+        let directional_light_directions =
+            list_arg(args.values_from_fn("--directional-light", extract_vec3));
         let directional_light_intensity: f32 =
             option_arg(args.opt_value_from_str("--directional-light-intensity")).unwrap_or(4.0);
-        let ambient_light_level: f32 =
-            option_arg(args.opt_value_from_str("--ambient")).unwrap_or(0.10);
+        let directional_light_color: Vec3 = option_arg(
+            args.opt_value_from_fn("--directional-light-color", |value| {
+                extract_array(value, [0.0; 3]).map(Vec3::from_array)
+            }),
+        )
+        .unwrap_or(Vec3::ONE);
+        let sun_time = option_arg(args.opt_value_from_fn("--sun-time", extract_time));
+        let daycycle: Option<f32> = option_arg(args.opt_value_from_str("--daycycle"));
+        let sun_latitude: f32 =
+            option_arg(args.opt_value_from_str("--sun-latitude")).unwrap_or(45.0);
+        let sun_day_of_year =
+            option_arg(args.opt_value_from_fn("--sun-date", extract_date)).unwrap_or(172);
+        let mut punctual_lights =
+            list_arg(args.values_from_fn("--point-light", extract_point_light));
+        punctual_lights.extend(list_arg(
+            args.values_from_fn("--spot-light", extract_spot_light),
+        ));
+        let punctual_light_intensity: f32 =
+            option_arg(args.opt_value_from_str("--point-light-intensity"))
+                .unwrap_or(lights::PUNCTUAL_LIGHT_INTENSITY);
+        let ambient_light_level: Option<f32> = option_arg(args.opt_value_from_str("--ambient"));
+        let ibl: Option<String> = option_arg(args.opt_value_from_str("--ibl"));
+        let ibl_key_light = args.contains("--ibl-key-light");
+        let benchmark_scene_count: Option<u32> =
+            option_arg(args.opt_value_from_str("--benchmark-scene"));
+        #[cfg(feature = "gltf")]
         let scale: Option<f32> = option_arg(args.opt_value_from_str("--scale"));
-        let shadow_distance: Option<f32> = option_arg(args.opt_value_from_str("--shadow-distance"));
+        let shadow_distance: f32 =
+            option_arg(args.opt_value_from_str("--shadow-distance")).unwrap_or(100.0);
+        #[cfg(feature = "gltf")]
         let shadow_resolution: Option<u16> =
             option_arg(args.opt_value_from_str("--shadow-resolution"));
+        #[cfg(feature = "gltf")]
         let gltf_disable_directional_light: bool =
             args.contains("--gltf-disable-directional-lights");
 
         // Controls
-        let walk_speed = args.value_from_str("--walk").unwrap_or(10.0_f32);
-        let run_speed = args.value_from_str("--run").unwrap_or(50.0_f32);
+        let walk_speed = positive_speed(
+            "--walk",
+            option_arg(args.opt_value_from_str("--walk")).unwrap_or(10.0),
+        );
+        let run_speed = positive_speed(
+            "--run",
+            option_arg(args.opt_value_from_str("--run")).unwrap_or(50.0),
+        );
+        println!(
+            "Walk speed {} units/s, run speed {} units/s",
+            walk_speed, run_speed
+        );
+        // Absolute coordinates move much further per event than raw relative motion.
+        let mouse_sensitivity: f64 = option_arg(args.opt_value_from_str("--mouse-sensitivity"))
+            .unwrap_or(if absolute_mouse { 0.25 } else { 1.0 });
+        if mouse_sensitivity <= 0.0 || !mouse_sensitivity.is_finite() {
+            eprintln!("--mouse-sensitivity must be a positive number");
+            std::process::exit(1);
+        }
+        let invert_y = args.contains("--invert-y");
+        let camera_damping: f32 =
+            option_arg(args.opt_value_from_str("--camera-damping")).unwrap_or(6.0);
+        if camera_damping <= 0.0 || !camera_damping.is_finite() {
+            eprintln!("--camera-damping must be a positive number");
+            std::process::exit(1);
+        }
+        let camera_damping = args.contains("--smooth-camera").then_some(camera_damping);
+        let key_bindings =
+            key_bindings::key_bindings(&list_arg(args.values_from_fn("--bind", extract_binding)));
+        #[cfg(feature = "gamepad")]
+        let gamepad_dead_zone: f32 =
+            option_arg(args.opt_value_from_str("--gamepad-dead-zone")).unwrap_or(0.15);
+        #[cfg(feature = "gamepad")]
+        if !(0.0..1.0).contains(&gamepad_dead_zone) {
+            eprintln!("--gamepad-dead-zone must be at least 0 and below 1");
+            std::process::exit(1);
+        }
+        #[cfg(feature = "gamepad")]
+        let gamepad = match gamepad::Gamepad::new(gamepad_dead_zone) {
+            Ok(gamepad) => Some(gamepad),
+            Err(e) => {
+                warn!("Gamepads are unavailable: {}", e);
+                None
+            }
+        };
+        let pan_speed: f32 = option_arg(args.opt_value_from_str("--pan-speed")).unwrap_or(1.0);
+        if pan_speed <= 0.0 || !pan_speed.is_finite() {
+            eprintln!("--pan-speed must be a positive number");
+            std::process::exit(1);
+        }
+        let idle_timeout: Option<f32> = option_arg(args.opt_value_from_str("--idle-timeout"));
+        if let Some(timeout) = idle_timeout {
+            if timeout <= 0.0 || !timeout.is_finite() {
+                eprintln!("--idle-timeout must be a positive number of seconds");
+                std::process::exit(1);
+            }
+        }
+        let idle_orbit_speed: f32 =
+            option_arg(args.opt_value_from_str("--idle-orbit-speed")).unwrap_or(10.0);
+        if !idle_orbit_speed.is_finite() {
+            eprintln!("--idle-orbit-speed must be a number of degrees per second");
+            std::process::exit(1);
+        }
+        let camera_fov: f32 = option_arg(args.opt_value_from_str("--fov")).unwrap_or(60.0);
+        if !FOV_RANGE.contains(&camera_fov) {
+            eprintln!(
+                "--fov must be between {} and {} degrees",
+                FOV_RANGE.start(),
+                FOV_RANGE.end()
+            );
+            std::process::exit(1);
+        }
+        let camera_near: f32 = option_arg(args.opt_value_from_str("--near")).unwrap_or(0.1);
+        if camera_near <= 0.0 || !camera_near.is_finite() {
+            eprintln!("--near must be a distance above 0");
+            std::process::exit(1);
+        }
+        let camera_transition_time: f32 =
+            option_arg(args.opt_value_from_str("--camera-transition")).unwrap_or(0.0);
+        if camera_transition_time < 0.0 || !camera_transition_time.is_finite() {
+            eprintln!("--camera-transition must be a number of seconds");
+            std::process::exit(1);
+        }
         let camera_default = [
             3.0,
             3.0,
@@ -378,6 +2176,50 @@ impl SceneViewer {
             -std::f32::consts::FRAC_PI_8,
             std::f32::consts::FRAC_PI_4,
         ];
+        let camera_log_path: Option<String> = option_arg(args.opt_value_from_str("--camera-log"));
+        #[cfg(not(target_arch = "wasm32"))]
+        let camera_script = args.contains("--camera-stdin").then(|| {
+            let dir: Option<String> = option_arg(args.opt_value_from_str("--camera-stdin-dir"));
+            camera_script::CameraScript::spawn(dir.unwrap_or_else(|| ".".to_owned()).into())
+        });
+        #[cfg(feature = "gltf")]
+        let stats_json_path: Option<String> = option_arg(args.opt_value_from_str("--stats-json"));
+        #[cfg(feature = "gltf")]
+        let log_stats = args.contains("--stats");
+        #[cfg(feature = "gltf")]
+        let suggest_flags = args.contains("--suggest-flags");
+        let look_at_target: Option<Vec3> =
+            option_arg(args.opt_value_from_fn("--look-at", |value| {
+                extract_array(value, [0.0; 3]).map(Vec3::from_array)
+            }));
+        let orbit_mode = args.contains("--orbit");
+        let projection_mode = if args.contains("--ortho") {
+            ProjectionMode::Orthographic
+        } else {
+            ProjectionMode::Perspective
+        };
+        #[cfg(feature = "gltf")]
+        let use_gltf_camera = args.contains("--use-gltf-camera");
+        #[cfg(feature = "gltf")]
+        let auto_fit = args.contains("--auto-fit");
+        #[cfg(feature = "gltf")]
+        let auto_frame = args.contains("--auto-frame");
+        #[cfg(feature = "gltf")]
+        let center_model = args.contains("--center-model");
+        #[cfg(feature = "gltf")]
+        let compare_file: Option<String> = option_arg(args.opt_value_from_str("--compare"));
+        #[cfg(feature = "gltf")]
+        let animation_name: Option<String> = option_arg(args.opt_value_from_str("--animation"));
+        #[cfg(feature = "gltf")]
+        let blend_animation_name: Option<String> =
+            option_arg(args.opt_value_from_str("--blend-animation"));
+        #[cfg(feature = "gltf")]
+        let animation_blend: f32 =
+            option_arg(args.opt_value_from_str("--animation-blend")).unwrap_or(0.5);
+        #[cfg(feature = "gltf")]
+        let animation_once = args.contains("--animation-once");
+        #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+        let watch = args.contains("--watch");
         let camera_info = args
             .value_from_str("--camera")
             .map_or(camera_default, |s: String| {
@@ -385,8 +2227,17 @@ impl SceneViewer {
             });
 
         // Free args
-        let file_to_load: Option<String> =
-            Some(args.free_from_str().unwrap_or("LinacLab.glb".to_owned()));
+        #[cfg(feature = "gltf")]
+        let mut files_to_load: Vec<String> = Vec::new();
+        #[cfg(feature = "gltf")]
+        while let Some(file) = option_arg(args.opt_free_from_str()) {
+            files_to_load.push(file);
+        }
+        #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+        let file_watch = match files_to_load.first() {
+            Some(file) if watch => Some((file.clone(), file_watch::FileWatch::new(file))),
+            _ => None,
+        };
 
         let remaining = args.finish();
 
@@ -406,221 +2257,2492 @@ impl SceneViewer {
             std::process::exit(1);
         }
 
-        let mut gltf_settings = rend3_gltf::GltfLoadSettings {
-            normal_direction,
-            enable_directional: !gltf_disable_directional_light,
-            ..Default::default()
-        };
-        if let Some(scale) = scale {
-            gltf_settings.scale = scale
+        if let Some(seconds) = daycycle {
+            if seconds <= 0.0 || !seconds.is_finite() {
+                eprintln!("--daycycle must be a positive number of seconds");
+                std::process::exit(1);
+            }
         }
-        if let Some(shadow_distance) = shadow_distance {
-            gltf_settings.directional_light_shadow_distance = shadow_distance;
+        let sun_time = sun_time.or(daycycle.map(|_| DAYCYCLE_START_TIME));
+        let sun = sun_time.map(|time| sun::SunPosition {
+            latitude: sun_latitude,
+            day_of_year: sun_day_of_year,
+            time,
+        });
+        let directional_light_directions = match sun {
+            Some(sun) => {
+                if !directional_light_directions.is_empty() {
+                    warn!("--sun-time overrides --directional-light");
+                }
+                if sun.is_below_horizon() && daycycle.is_none() {
+                    warn!("The sun is below the horizon at the given --sun-time");
+                }
+                vec![sun.light_direction()]
+            }
+            None => directional_light_directions,
+        };
+
+        #[cfg(feature = "gltf")]
+        let gltf_settings = {
+            let mut gltf_settings = rend3_gltf::GltfLoadSettings {
+                normal_direction,
+                enable_directional: !gltf_disable_directional_light,
+                directional_light_shadow_distance: shadow_distance,
+                ..Default::default()
+            };
+            if let Some(scale) = scale {
+                gltf_settings.scale = scale
+            }
+            if let Some(shadow_resolution) = shadow_resolution {
+                gltf_settings.directional_light_resolution = shadow_resolution;
+            }
+            gltf_settings
+        };
+        if ibl_key_light && ibl.is_none() {
+            warn!("--ibl-key-light needs an --ibl environment, ignoring");
         }
-        if let Some(shadow_resolution) = shadow_resolution {
-            gltf_settings.directional_light_resolution = shadow_resolution;
+        let mut directional_light_color = directional_light_color;
+        let mut directional_light_intensity = directional_light_intensity;
+        let mut directional_light_directions = directional_light_directions;
+        let ambient_color = ibl.and_then(|path| {
+            warn!("rend3 has no image based lighting, approximating --ibl with ambient light");
+            let loader = asset_loader("", &asset_url);
+            let data = match pollster::block_on(loader.get_asset(AssetPath::External(&path))) {
+                Ok(data) => data,
+                Err(e) => {
+                    error_policy.report(format_args!("Failed to read environment {}: {}", path, e));
+                    return None;
+                }
+            };
+            match image::load_from_memory(&data) {
+                Ok(image) => {
+                    let mut radiance = environment::average_radiance(&image);
+                    info!("Environment {} average radiance {}", path, radiance);
+                    if !ibl_key_light {
+                        return Some(radiance);
+                    }
+                    if !directional_light_directions.is_empty() {
+                        warn!("--directional-light or --sun-time take precedence over --ibl-key-light");
+                        return Some(radiance);
+                    }
+                    match environment::key_light(&image) {
+                        Some(key) => {
+                            info!(
+                                "Key light from {} travelling along {} with color {} at intensity {}",
+                                path, key.direction, key.color, key.intensity
+                            );
+                            directional_light_directions.push(key.direction);
+                            directional_light_color = key.color;
+                            directional_light_intensity = key.intensity;
+                            // The average over the sphere includes the key light, which now
+                            // comes from the directional light instead.
+                            radiance = (radiance
+                                - key.irradiance / (4.0 * std::f32::consts::PI))
+                                .max(Vec3::ZERO);
+                        }
+                        None => warn!("Environment {} has no bright spot for --ibl-key-light", path),
+                    }
+                    Some(radiance)
+                }
+                Err(e) => {
+                    error_policy
+                        .report(format_args!("Failed to decode environment {}: {}", path, e));
+                    None
+                }
+            }
+        });
+        let ambient_light_level =
+            ambient_light_level.unwrap_or(if ambient_color.is_some() { 1.0 } else { 0.10 });
+        let ambient_color = ambient_color.unwrap_or(Vec3::ONE);
+
+        #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+        let puppet_watch = match puppet {
+            Some(ref puppet) if watch_puppet => {
+                // The puppet is an internal asset, relative to the crate unless it's absolute.
+                let path = if Path::new(puppet).is_absolute() {
+                    puppet.clone()
+                } else {
+                    format!("{}/{}", env!("CARGO_MANIFEST_DIR"), puppet)
+                };
+                Some((path.clone(), file_watch::FileWatch::new(path)))
+            }
+            None if watch_puppet => {
+                warn!("--puppet-watch needs a --puppet, ignoring");
+                None
+            }
+            _ => None,
+        };
+        #[cfg(feature = "puppet")]
+        let inox_model = puppet.and_then(|puppet| {
+            let loader = asset_loader("", &asset_url);
+            let data = match pollster::block_on(loader.get_asset(AssetPath::Internal(&puppet))) {
+                Ok(data) => data,
+                Err(e) => {
+                    error_policy.report(format_args!("Failed to read puppet {}: {}", puppet, e));
+                    return None;
+                }
+            };
+            match parse_inp(data.as_slice()) {
+                Ok(inox_model) => Some(inox_model),
+                Err(e) => {
+                    error_policy.report(format_args!("Failed to parse puppet {}: {}", puppet, e));
+                    None
+                }
+            }
+        });
+        #[cfg(feature = "puppet")]
+        if let (true, Some(ref inox_model)) = (list_puppet_params, &inox_model) {
+            print_puppet_params(&inox_model.puppet);
+            std::process::exit(0);
         }
-        let inox_model = parse_inp(
-            pollster::block_on(async {
-                let loader = rend3_framework::AssetLoader::new_local(
-                    concat!(env!("CARGO_MANIFEST_DIR"), "/"),
-                    "",
-                    "http://localhost:8000/",
-                );
-                loader
-                    .get_asset(AssetPath::Internal(&puppet))
-                    .await
-                    .unwrap()
-            })
-            .as_slice(),
-        )
-        .unwrap();
 
-        Self {
+        let mut viewer = Self {
+            error_policy,
             absolute_mouse,
+            grab_enabled,
+            #[cfg(any(feature = "gltf", feature = "skybox"))]
+            asset_url,
             desired_backend,
             desired_device_name,
             desired_profile: desired_mode,
-            file_to_load,
+            #[cfg(feature = "gltf")]
+            files_to_load,
+            #[cfg(feature = "gltf")]
+            compare_file,
+            #[cfg(feature = "puppet")]
             inox_renderer: None,
+            #[cfg(feature = "puppet")]
             inox_model,
             walk_speed,
             run_speed,
+            mouse_sensitivity,
+            invert_y,
+            #[cfg(feature = "gamepad")]
+            gamepad,
+            pan_speed,
+            #[cfg(feature = "gltf")]
             gltf_settings,
-            directional_light_direction,
+            #[cfg(feature = "gltf")]
+            base_color_space,
+            #[cfg(feature = "gltf")]
+            validate,
+            #[cfg(feature = "gltf")]
+            morph_weights,
+            #[cfg(feature = "gltf")]
+            emissive_scale,
+            shadow_distance,
+            directional_light_directions,
             directional_light_intensity,
-            directional_light: None,
+            directional_light_color,
+            directional_lights: Vec::new(),
+            sun,
+            daycycle,
+            punctual_lights,
+            punctual_light_intensity,
+            punctual_light_handles: Vec::new(),
+            show_light_gizmos,
+            show_shadow_bounds,
+            light_gizmos: None,
             ambient_light_level,
+            ambient_color,
+            benchmark_scene_count,
+            benchmark_objects: Vec::new(),
             present_mode,
+            low_latency,
+            supported_present_modes: Vec::new(),
+            alpha_mode,
+            surface_usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
+            handedness,
+            msaa,
             samples,
-            timestamp_start,
+            render_scale: default_render_scale(),
+            min_frame_time: max_fps.map(|fps| Duration::from_secs_f32(1.0 / fps)),
+            next_frame: Instant::now(),
+            power_saver,
+            saving_power: false,
+            last_power_check: None,
+            target_frame_time: target_fps.map(|fps| Duration::from_secs_f32(1.0 / fps)),
+            auto_exposure_speed,
+            auto_exposure: None,
+            show_skybox,
+            clear_color,
+            #[cfg(feature = "skybox")]
+            skybox_layout,
+            scene_time: 0.0,
+            frozen_time: None,
             fullscreen,
+            #[cfg(feature = "puppet")]
             inox_texture: None,
+            #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+            puppet_watch,
+            #[cfg(feature = "puppet")]
+            puppet_enabled,
+            #[cfg(feature = "puppet")]
+            puppet_scale,
+            #[cfg(feature = "puppet")]
+            puppet_position,
+            #[cfg(feature = "puppet")]
+            puppet_track_mouse,
+            #[cfg(feature = "puppet")]
+            puppet_params,
+            crossfade_duration,
+            crossfade: None,
             scancode_status: FastHashMap::default(),
+            camera_damping,
+            camera_velocity: Vec3A::ZERO,
+            key_bindings,
             camera_pitch: camera_info[3],
             camera_yaw: camera_info[4],
             camera_location: Vec3A::new(camera_info[0], camera_info[1], camera_info[2]),
+            camera_fov,
+            fov_zoom: 0.0,
+            camera_near,
+            projection_mode,
+            ortho_height: 1.0,
+            #[cfg(feature = "gltf")]
+            use_gltf_camera,
+            #[cfg(feature = "gltf")]
+            scene_cameras: Vec::new(),
+            #[cfg(feature = "gltf")]
+            scene_camera: None,
+            #[cfg(feature = "gltf")]
+            free_camera: CameraPose {
+                location: Vec3A::ZERO,
+                pitch: 0.0,
+                yaw: 0.0,
+                fov: 60.0,
+            },
+            #[cfg(feature = "gltf")]
+            auto_fit,
+            #[cfg(feature = "gltf")]
+            auto_frame,
+            #[cfg(feature = "gltf")]
+            center_model,
+            #[cfg(feature = "gltf")]
+            animation_name,
+            #[cfg(feature = "gltf")]
+            blend_animation_name,
+            #[cfg(feature = "gltf")]
+            animation_blend,
+            #[cfg(feature = "gltf")]
+            animation: None,
+            #[cfg(feature = "gltf")]
+            animation_once,
+            #[cfg(feature = "gltf")]
+            animation_paused: false,
+            camera_log_path,
+            #[cfg(not(target_arch = "wasm32"))]
+            camera_script,
+            #[cfg(not(target_arch = "wasm32"))]
+            frame_capture: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            capture_count: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            resume_after_capture: false,
+            #[cfg(feature = "gltf")]
+            log_stats,
+            #[cfg(feature = "gltf")]
+            stats_json_path,
+            #[cfg(feature = "gltf")]
+            suggest_flags,
+            look_at_target,
+            orbit_mode,
+            orbit_target: Vec3::ZERO,
+            orbit_radius: ORBIT_DEFAULT_DISTANCE,
+            camera_log: None,
             previous_profiling_stats: None,
+            gpu_timings: gpu_timings::GpuTimingSummary::default(),
             timestamp_last_second: Instant::now(),
+            stats_interval: Duration::from_secs_f32(stats_interval),
+            percentiles,
+            bench,
             timestamp_last_frame: Instant::now(),
             frame_times: histogram::Histogram::new(),
+            frame_graph: frame_graph::FrameTimeGraph::new(240),
+            show_frame_graph,
+            show_key_help: false,
+            #[cfg(feature = "gltf")]
+            show_wireframe,
+            #[cfg(feature = "gltf")]
+            wireframe_color,
+            #[cfg(feature = "gltf")]
+            wireframe_width,
+            debug_line_width,
+            #[cfg(feature = "gltf")]
+            wireframe: None,
+            #[cfg(feature = "gltf")]
+            show_coordinates,
+            #[cfg(feature = "gltf")]
+            last_pick: None,
+            #[cfg(feature = "gltf")]
+            cursor_hit: None,
+            debug_lines: None,
+            #[cfg(feature = "gltf")]
+            depth_of_field_settings,
+            #[cfg(feature = "gltf")]
+            depth_of_field: None,
             last_mouse_delta: None,
+            left_mouse_down: false,
+            middle_mouse_down: false,
+            right_mouse_down: false,
+            cursor_position: None,
+            idle_timeout: idle_timeout.map(Duration::from_secs_f32),
+            idle_orbit_speed: idle_orbit_speed.to_radians(),
+            last_input: Instant::now(),
+            idle: false,
+            camera_transition_time,
+            camera_transition: None,
+            record_input_path,
+            replay_input_path,
+            input_recorder: None,
+            input_replay: None,
+
+            load_events: None,
+            loading: false,
+            download_progress: (0, 0),
+            #[cfg(feature = "gltf")]
+            scene: None,
+            #[cfg(feature = "gltf")]
+            compare_scene: None,
+            #[cfg(feature = "gltf")]
+            other_scenes: VecDeque::new(),
+            #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+            file_watch,
+            #[cfg(feature = "gltf")]
+            material_adjustment: material_override::MaterialAdjustment::default(),
+            #[cfg(feature = "gltf")]
+            compare_mode: CompareMode::Split,
+            wipe_position: 0.5,
+            dragging_wipe: false,
 
             grabber: None,
-        }
+            overlay: None,
+            startup_tweaks: None,
+        };
+        viewer.ortho_height = viewer.matching_ortho_height();
+        viewer.startup_tweaks = Some(viewer.tweaks());
+        viewer
     }
-}
-impl rend3_framework::App for SceneViewer {
-    const HANDEDNESS: rend3::types::Handedness = rend3::types::Handedness::Right;
-
-    fn create_window(
-        &mut self,
-        builder: WindowBuilder,
-    ) -> Result<
-        (
-            winit::event_loop::EventLoop<UserResizeEvent<()>>,
-            winit::window::Window,
-        ),
-        winit::error::EventLoopError,
-    > {
-        profiling::scope!("creating window");
 
-        let event_loop = winit::event_loop::EventLoopBuilder::with_user_event().build()?;
-        let window = builder.build(&event_loop).expect("Could not build window");
+    fn tweaks(&self) -> Tweaks {
+        Tweaks {
+            camera_location: self.camera_location,
+            camera_pitch: self.camera_pitch,
+            camera_yaw: self.camera_yaw,
+            camera_fov: self.camera_fov,
+            projection_mode: self.projection_mode,
+            ortho_height: self.ortho_height,
+            orbit_mode: self.orbit_mode,
+            sun: self.sun,
+            ambient_light_level: self.ambient_light_level,
+            show_skybox: self.show_skybox,
+            show_light_gizmos: self.show_light_gizmos,
+            show_shadow_bounds: self.show_shadow_bounds,
+            show_frame_graph: self.show_frame_graph,
+            #[cfg(feature = "gltf")]
+            show_wireframe: self.show_wireframe,
+            #[cfg(feature = "gltf")]
+            show_coordinates: self.show_coordinates,
+            #[cfg(feature = "gltf")]
+            material_adjustment: self.material_adjustment,
+            #[cfg(feature = "gltf")]
+            animation_blend: self.animation_blend,
+            #[cfg(feature = "gltf")]
+            animation_paused: self.animation_paused,
+            #[cfg(feature = "gltf")]
+            compare_mode: self.compare_mode,
+            #[cfg(feature = "puppet")]
+            puppet_enabled: self.puppet_enabled,
+            #[cfg(feature = "puppet")]
+            puppet_scale: self.puppet_scale,
+        }
+    }
 
-        #[cfg(target_arch = "wasm32")]
+    /// Puts everything the runtime keys changed back the way it was at startup, and lets time
+    /// run again.
+    fn reset_tweaks(&mut self, window: &Window, renderer: &Renderer) {
+        let Some(tweaks) = self.startup_tweaks.clone() else {
+            return;
+        };
+        self.camera_location = tweaks.camera_location;
+        self.camera_pitch = tweaks.camera_pitch;
+        self.camera_yaw = tweaks.camera_yaw;
+        self.camera_fov = tweaks.camera_fov;
+        self.projection_mode = tweaks.projection_mode;
+        self.ortho_height = tweaks.ortho_height;
+        self.idle = false;
+        self.camera_transition = None;
+        self.camera_velocity = Vec3A::ZERO;
+        #[cfg(feature = "gltf")]
         {
-            use winit::platform::web::WindowExtWebSys;
+            self.scene_camera = None;
+        }
+        self.set_orbit_mode(window, tweaks.orbit_mode);
+        if let (Some(sun), Some(startup_sun)) = (self.sun, tweaks.sun) {
+            self.scrub_sun(renderer, startup_sun.time - sun.time);
+        }
+        self.ambient_light_level = tweaks.ambient_light_level;
+        self.show_skybox = tweaks.show_skybox;
+        if self.show_light_gizmos != tweaks.show_light_gizmos {
+            self.set_light_gizmos_visible(renderer, tweaks.show_light_gizmos);
+        }
+        self.show_shadow_bounds = tweaks.show_shadow_bounds;
+        self.show_frame_graph = tweaks.show_frame_graph;
+        #[cfg(feature = "gltf")]
+        {
+            self.show_wireframe = tweaks.show_wireframe;
+            self.show_coordinates = tweaks.show_coordinates;
+            self.last_pick = None;
+            self.compare_mode = tweaks.compare_mode;
+            self.animation_paused = tweaks.animation_paused;
+            if self.material_adjustment != tweaks.material_adjustment {
+                self.material_adjustment = tweaks.material_adjustment;
+                for scene in self
+                    .scene
+                    .iter()
+                    .chain(&self.compare_scene)
+                    .chain(&self.other_scenes)
+                {
+                    self.adjust_materials(renderer, scene);
+                }
+            }
+            self.animation_blend = tweaks.animation_blend;
+            if let Some(ref mut player) = self.animation {
+                player.blend = tweaks.animation_blend;
+            }
+        }
+        #[cfg(feature = "puppet")]
+        {
+            self.puppet_enabled = tweaks.puppet_enabled;
+            self.set_puppet_scale(tweaks.puppet_scale);
+        }
+        self.set_frozen(false);
+        println!("Reset to the startup settings");
+    }
 
-            let canvas = window.canvas().unwrap();
-            let style = canvas.style();
-            style.set_property("width", "100%").unwrap();
-            style.set_property("height", "100%").unwrap();
+    /// Color of the directional lights, following the sun during --daycycle.
+    fn directional_light_color(&self) -> Vec3 {
+        match (self.sun, self.daycycle) {
+            (Some(sun), Some(_)) => self.directional_light_color * sun.daylight(),
+            _ => self.directional_light_color,
+        }
+    }
 
-            web_sys::window()
-                .and_then(|win| win.document())
-                .and_then(|doc| doc.body())
-                .and_then(|body| body.append_child(&canvas).ok())
-                .expect("couldn't append canvas to document body");
+    #[cfg(feature = "puppet")]
+    fn set_puppet_scale(&mut self, scale: f32) {
+        self.puppet_scale = scale;
+        if let Some(ref mut inox_renderer) = self.inox_renderer {
+            inox_renderer.camera.scale = Vec2::splat(scale);
         }
+    }
 
-        Ok((event_loop, window))
+    /// Creates the renderer of the loaded puppet, replacing any earlier one.
+    #[cfg(feature = "puppet")]
+    fn create_puppet_renderer(&mut self, renderer: &Renderer, size: UVec2) {
+        let Some(ref inox_model) = self.inox_model else {
+            return;
+        };
+        let mut inox_renderer = inox2d_wgpu::Renderer::new(
+            &renderer.device,
+            &renderer.queue,
+            wgpu::TextureFormat::Bgra8Unorm,
+            inox_model,
+            size,
+        );
+        inox_renderer.camera.scale = Vec2::splat(self.puppet_scale);
+        inox_renderer.camera.position = self.puppet_position;
+        self.inox_renderer = Some(inox_renderer);
     }
 
-    fn create_iad<'a>(
-        &'a mut self,
-    ) -> std::pin::Pin<
-        Box<dyn std::future::Future<Output = anyhow::Result<rend3::InstanceAdapterDevice>> + 'a>,
-    > {
-        Box::pin(async move {
-            Ok(rend3::create_iad(
-                self.desired_backend,
-                self.desired_device_name.clone(),
-                self.desired_profile,
-                Some(Features::ADDRESS_MODE_CLAMP_TO_BORDER),
-            )
-            .await?)
-        })
+    /// Parses the --puppet file again for --puppet-watch, keeping the old puppet if that fails.
+    #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+    fn reload_puppet(&mut self, renderer: &Renderer, path: &str, size: UVec2) {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) => {
+                self.error_policy
+                    .report(format_args!("Failed to read puppet {}: {}", path, e));
+                return;
+            }
+        };
+        match parse_inp(data.as_slice()) {
+            Ok(inox_model) => {
+                info!("Reloaded puppet {}", path);
+                self.inox_model = Some(inox_model);
+                self.create_puppet_renderer(renderer, size);
+            }
+            Err(e) => self
+                .error_policy
+                .report(format_args!("Failed to parse puppet {}: {}", path, e)),
+        }
     }
 
-    fn create_base_rendergraph(
-        &mut self,
-        renderer: &Arc<Renderer>,
-        spp: &rend3::ShaderPreProcessor,
-    ) -> BaseRenderGraph {
-        BaseRenderGraph::new(renderer, spp)
+    /// Value of the puppet's head yaw and pitch parameter, each in -1..=1.
+    #[cfg(feature = "puppet")]
+    fn puppet_head(&self, resolution: UVec2) -> Vec2 {
+        if !self.puppet_track_mouse {
+            let t = self.scene_time;
+            return vec2(t.cos(), t.sin());
+        }
+        match self.cursor_position {
+            Some(cursor) => {
+                let centered = cursor.as_vec2() / resolution.max(UVec2::ONE).as_vec2() * 2.0 - 1.0;
+                // Up on screen is a positive pitch.
+                vec2(centered.x, -centered.y).clamp(Vec2::NEG_ONE, Vec2::ONE)
+            }
+            None => Vec2::ZERO,
+        }
     }
 
-    fn sample_count(&self) -> SampleCount {
-        self.samples
+    fn change_ambient(&mut self, change: f32) {
+        self.ambient_light_level = (self.ambient_light_level + change).clamp(0.0, MAX_AMBIENT);
+        println!("Ambient light {:.2}", self.ambient_light_level);
     }
 
-    fn present_mode(&self) -> rend3::types::PresentMode {
-        self.present_mode
+    /// Ambient light level, dimmed at night during --daycycle.
+    fn ambient_light_level(&self) -> f32 {
+        match (self.sun, self.daycycle) {
+            (Some(sun), Some(_)) => {
+                let daylight = sun.daylight().max_element();
+                self.ambient_light_level
+                    * (DAYCYCLE_NIGHT_AMBIENT + (1.0 - DAYCYCLE_NIGHT_AMBIENT) * daylight)
+            }
+            _ => self.ambient_light_level,
+        }
     }
 
-    fn scale_factor(&self) -> f32 {
-        // Android has very low memory bandwidth, so lets run internal buffers at half
-        // res by default
-        cfg_if::cfg_if! {
-            if #[cfg(target_os = "android")] {
-                0.5
-            } else {
-                1.0
+    /// Directions of the directional lights as they currently are, including runtime changes.
+    fn directional_light_directions(&self) -> Vec<Vec3> {
+        match self.sun {
+            Some(sun) => vec![sun.light_direction()],
+            None => self.directional_light_directions.clone(),
+        }
+    }
+
+    #[cfg_attr(not(feature = "gltf"), allow(unused_variables))]
+    fn poll_load_events(&mut self, renderer: &Renderer, resolution: UVec2) {
+        let Some(ref load_events) = self.load_events else {
+            return;
+        };
+        let events: Vec<_> = load_events.try_iter().collect();
+
+        for event in events {
+            match event {
+                #[cfg(feature = "gltf")]
+                LoadEvent::Scene(scene) => {
+                    let cameras = scene.document.as_ref().map_or_else(Vec::new, |document| {
+                        scene_info::cameras(document, self.gltf_settings.scale)
+                    });
+                    for (idx, camera) in cameras.iter().enumerate() {
+                        let projection = camera.vfov.map_or_else(
+                            || "orthographic".to_owned(),
+                            |vfov| format!("{:.1} degree fov", vfov),
+                        );
+                        info!(
+                            "Camera {}: {} at {}, {}",
+                            idx,
+                            camera.name.as_deref().unwrap_or("<unnamed>"),
+                            camera.position,
+                            projection
+                        );
+                    }
+                    self.scene_cameras = cameras;
+                    self.scene_camera = None;
+                    if self.use_gltf_camera {
+                        if self.scene_cameras.is_empty() {
+                            info!("The gltf has no cameras, keeping the default camera");
+                        } else {
+                            self.cycle_scene_camera();
+                        }
+                    }
+                    self.start_animation(&scene.scene);
+                    if let Some((min, max)) = scene.stats.as_ref().and_then(|stats| stats.bounds) {
+                        let aspect_ratio = resolution.x as f32 / resolution.y.max(1) as f32;
+                        let view_proj = self.projection_matrix(aspect_ratio) * self.view_matrix();
+                        if self.auto_frame {
+                            self.jump_camera(|viewer| viewer.frame_bounds(min, max, aspect_ratio));
+                        } else if bounds_outside_frustum(view_proj, min, max) {
+                            if self.auto_fit {
+                                warn!(
+                                    "The scene is out of view, moving the camera to see all of it"
+                                );
+                                self.jump_camera(|viewer| {
+                                    viewer.frame_bounds(min, max, aspect_ratio)
+                                });
+                            } else {
+                                warn!(
+                                    "The scene is out of view, it is between {} and {}. \
+                                     Pass --auto-fit to move the camera to it",
+                                    min, max
+                                );
+                            }
+                        }
+                    }
+                    if let (true, Some(stats)) = (self.suggest_flags, &scene.stats) {
+                        if let Some((min, max)) = stats.bounds {
+                            let aspect_ratio = resolution.x as f32 / resolution.y.max(1) as f32;
+                            self.print_suggested_flags(&stats.file, min, max, aspect_ratio);
+                        }
+                    }
+                    self.log_stats(&scene);
+                    if let (Some(path), Some(stats)) = (&self.stats_json_path, &scene.stats) {
+                        match std::fs::write(path, stats.to_json()) {
+                            Ok(()) => info!("Wrote scene statistics to {}", path),
+                            Err(e) => self.error_policy.report(format_args!(
+                                "Failed to write scene statistics to {}: {}",
+                                path, e
+                            )),
+                        }
+                    }
+                    let scene = *scene;
+                    self.add_triangles(renderer, &scene);
+                    if self.materials_adjusted(&scene) {
+                        self.adjust_materials(renderer, &scene);
+                    }
+                    self.scene = Some(scene);
+                    self.last_pick = None;
+                }
+                #[cfg(feature = "gltf")]
+                LoadEvent::CompareScene(scene) => {
+                    let scene = *scene;
+                    self.log_stats(&scene);
+                    self.add_triangles(renderer, &scene);
+                    if self.materials_adjusted(&scene) {
+                        self.adjust_materials(renderer, &scene);
+                    }
+                    self.compare_scene = Some(scene);
+                    self.last_pick = None;
+                }
+                #[cfg(feature = "gltf")]
+                LoadEvent::OtherScene(scene) => {
+                    let mut scene = *scene;
+                    self.log_stats(&scene);
+                    // Dropped before the next frame is rendered, so it never shows up.
+                    scene.instance = None;
+                    if self.materials_adjusted(&scene) {
+                        self.adjust_materials(renderer, &scene);
+                    }
+                    info!("Loaded {}, press Tab to switch to it", scene.path);
+                    self.other_scenes.push_back(scene);
+                    // Stands in for the first file if that one failed to load.
+                    if self.scene.is_none() {
+                        self.switch_scene(renderer);
+                    }
+                }
+                #[cfg(feature = "gltf")]
+                LoadEvent::Download(progress) => match progress {
+                    asset_fetch::DownloadProgress::Started { total } => {
+                        self.download_progress.1 += total.unwrap_or(0);
+                    }
+                    asset_fetch::DownloadProgress::Received { bytes } => {
+                        self.download_progress.0 += bytes;
+                    }
+                },
+                LoadEvent::Finished => {
+                    info!("Finished loading");
+                    self.loading = false;
+                    if let Some(ref mut crossfade) = self.crossfade {
+                        crossfade.start();
+                    }
+                }
             }
         }
     }
 
-    fn setup<'a>(
-        &'a mut self,
-        _event_loop: &winit::event_loop::EventLoop<rend3_framework::UserResizeEvent<()>>,
-        window: &'a winit::window::Window,
-        renderer: &'a Arc<Renderer>,
-        routines: &'a Arc<rend3_framework::DefaultRoutines>,
-        _surface_format: rend3::types::TextureFormat,
-    ) {
-        self.grabber = Some(rend3_framework::Grabber::new(window));
+    /// Moves the camera back along its view direction until the box from `min` to `max` fits
+    /// into view, centered. In orbit mode the middle of the box becomes the pivot.
+    #[cfg(feature = "gltf")]
+    fn frame_bounds(&mut self, min: Vec3, max: Vec3, aspect_ratio: f32) {
+        let center = (min + max) * 0.5;
+        let radius = ((max - min).length() * 0.5).max(MIN_ORBIT_DISTANCE);
+        self.orbit_target = center;
+        self.place_orbit_camera(self.framing_distance(radius, aspect_ratio));
+        self.ortho_height = 2.0 * radius / aspect_ratio.min(1.0);
+    }
 
-        if let Some(direction) = self.directional_light_direction {
-            self.directional_light = Some(renderer.add_directional_light(DirectionalLight {
-                color: Vec3::splat(1.0),
-                intensity: self.directional_light_intensity,
-                direction,
-                distance: self.gltf_settings.directional_light_shadow_distance,
-                resolution: 2048,
-            }));
+    /// Moves the camera to fit the shown scene, and the --compare scene, into view.
+    #[cfg(feature = "gltf")]
+    fn frame_scene(&mut self, aspect_ratio: f32) {
+        let Some((min, max)) = self
+            .scene
+            .iter()
+            .chain(&self.compare_scene)
+            .filter_map(|scene| scene.stats.as_ref()?.bounds)
+            .reduce(|(min, max), (other_min, other_max)| (min.min(other_min), max.max(other_max)))
+        else {
+            println!("No scene to frame");
+            return;
+        };
+        self.jump_camera(|viewer| viewer.frame_bounds(min, max, aspect_ratio));
+    }
+
+    /// How far from a sphere of `radius` the camera needs to be to see all of it.
+    #[cfg(feature = "gltf")]
+    fn framing_distance(&self, radius: f32, aspect_ratio: f32) -> f32 {
+        let half_vfov = (self.camera_fov * 0.5).to_radians();
+        let half_hfov = (half_vfov.tan() * aspect_ratio).atan();
+        radius / half_vfov.min(half_hfov).sin()
+    }
+
+    /// Prints the bounds of a loaded scene and flags that frame it from the current direction,
+    /// as a command line to paste, for --suggest-flags.
+    #[cfg(feature = "gltf")]
+    fn print_suggested_flags(&self, file: &str, min: Vec3, max: Vec3, aspect_ratio: f32) {
+        let format_vec3 = |v: Vec3| format!("{:.3},{:.3},{:.3}", v.x, v.y, v.z);
+        let size = max - min;
+        println!(
+            "Scene bounds from {} to {}, {} in size",
+            format_vec3(min),
+            format_vec3(max),
+            format_vec3(size)
+        );
+
+        let largest = size.max_element();
+        let rescale = if largest > 0.0 && !SUGGESTED_SCENE_SIZES.contains(&largest) {
+            SUGGESTED_SCENE_SIZE / largest
+        } else {
+            1.0
+        };
+        // Scaling happens around the origin, so the middle moves along.
+        let center = (min + max) * 0.5 * rescale;
+        let radius = (size.length() * 0.5 * rescale).max(MIN_ORBIT_DISTANCE);
+        let distance = self.framing_distance(radius, aspect_ratio);
+        let (forward, _, _) = self.camera_axes();
+        let camera = Vec3::from(Vec3A::from(center) - forward * distance);
+        // Far enough out to keep depth precision, close enough not to clip the scene.
+        let near = ((distance - radius) * 0.1).clamp(0.001, 1.0);
+
+        let mut flags = Vec::new();
+        if rescale != 1.0 {
+            flags.push(format!("--scale {}", self.gltf_settings.scale * rescale));
+        }
+        flags.push(format!(
+            "--camera {},{:.4},{:.4}",
+            format_vec3(camera),
+            self.camera_pitch,
+            self.camera_yaw
+        ));
+        flags.push(format!("--look-at {}", format_vec3(center)));
+        flags.push(format!("--near {}", near));
+        println!(
+            "Suggested command line: {} {} {}",
+            env!("CARGO_PKG_NAME"),
+            file,
+            flags.join(" ")
+        );
+    }
+
+    /// Turns the camera towards `target` without moving it.
+    fn look_at(&mut self, target: Vec3) {
+        let direction = target - Vec3::from(self.camera_location);
+        if direction.length_squared() < 1e-8 {
+            return;
+        }
+        (self.camera_pitch, self.camera_yaw) = pitch_yaw_towards(direction * self.camera_mirror());
+    }
+
+    /// Forward, side (to the left) and up directions of the camera.
+    fn camera_axes(&self) -> (Vec3A, Vec3A, Vec3A) {
+        let rotation = Mat3A::from_euler(
+            glam::EulerRot::XYZ,
+            -self.camera_pitch,
+            -self.camera_yaw,
+            0.0,
+        )
+        .transpose();
+        let mirror = Vec3A::from(self.camera_mirror());
+        (
+            -rotation.z_axis * mirror,
+            -rotation.x_axis * mirror,
+            rotation.y_axis * mirror,
+        )
+    }
+
+    /// Switches between flying and orbiting. Orbiting pivots around the --look-at point if there
+    /// is one, otherwise around the point [`ORBIT_DEFAULT_DISTANCE`] in front of the camera.
+    fn set_orbit_mode(&mut self, window: &Window, orbit_mode: bool) {
+        self.orbit_mode = orbit_mode;
+        if !orbit_mode {
+            return;
+        }
+        // Orbiting needs the cursor to drag with.
+        self.grabber.as_mut().unwrap().request_ungrab(window);
+        match self.look_at_target {
+            Some(target) => {
+                self.orbit_target = target;
+                self.look_at(target);
+                self.orbit_radius = self.orbit_distance().max(MIN_ORBIT_DISTANCE);
+            }
+            None => {
+                let (forward, _, _) = self.camera_axes();
+                self.orbit_target =
+                    (self.camera_location + forward * ORBIT_DEFAULT_DISTANCE).into();
+                self.orbit_radius = ORBIT_DEFAULT_DISTANCE;
+            }
+        }
+    }
+
+    /// Places the camera `distance` away from the orbit pivot, looking at it, and orbits at that
+    /// radius from then on.
+    fn place_orbit_camera(&mut self, distance: f32) {
+        self.orbit_radius = distance;
+        let (forward, _, _) = self.camera_axes();
+        self.camera_location = Vec3A::from(self.orbit_target) - forward * distance;
+    }
+
+    fn orbit_distance(&self) -> f32 {
+        (Vec3A::from(self.orbit_target) - self.camera_location).length()
+    }
+
+    fn orbit(&mut self, delta: DVec2) {
+        // Dragging right turns the scene right, so the camera goes left.
+        self.rotate_camera(-delta * ORBIT_DRAG_SCALE);
+        self.place_orbit_camera(self.orbit_radius);
+    }
+
+    /// Moves the camera, and the pivot along with it, sideways and up or down with the cursor.
+    /// Orbiting pans further the further away the pivot is, flying goes by the walk speed.
+    fn pan(&mut self, delta: DVec2) {
+        let (_, side, up) = self.camera_axes();
+        let per_pixel = if self.orbit_mode {
+            self.orbit_radius * PAN_PER_PIXEL
+        } else {
+            self.walk_speed * FLY_PAN_SECONDS_PER_PIXEL
+        };
+        let offset = (side * delta.x as f32 + up * delta.y as f32) * per_pixel * self.pan_speed;
+        self.camera_location += offset;
+        self.orbit_target += Vec3::from(offset);
+    }
+
+    fn camera_pose(&self) -> CameraPose {
+        CameraPose {
+            location: self.camera_location,
+            pitch: self.camera_pitch,
+            yaw: self.camera_yaw,
+            fov: self.camera_fov,
+        }
+    }
+
+    fn set_camera_pose(&mut self, pose: CameraPose) {
+        self.camera_location = pose.location;
+        self.camera_pitch = pose.pitch;
+        self.camera_yaw = pose.yaw;
+        self.camera_fov = pose.fov;
+    }
+
+    /// Runs `jump`, which moves the camera somewhere else. With --camera-transition the camera
+    /// is then eased there from where it was, instead of snapping.
+    fn jump_camera(&mut self, jump: impl FnOnce(&mut Self)) {
+        let from = self.camera_pose();
+        jump(self);
+        if self.camera_transition_time > 0.0 {
+            let to = self.camera_pose();
+            self.set_camera_pose(from);
+            self.camera_transition = Some(CameraTransition {
+                from,
+                to,
+                elapsed: 0.0,
+            });
+        }
+    }
+
+    /// Applies part of the field of view change left from zooming.
+    fn update_fov_zoom(&mut self, seconds: f32) {
+        if self.fov_zoom == 0.0 {
+            return;
+        }
+        let step = if self.fov_zoom.abs() < 0.01 {
+            self.fov_zoom
+        } else {
+            self.fov_zoom * (1.0 - (-seconds * FOV_ZOOM_SPEED).exp())
+        };
+        let fov = (self.camera_fov + step).clamp(*FOV_RANGE.start(), *FOV_RANGE.end());
+        // Zooming past the limits is dropped rather than kept for later.
+        self.fov_zoom = if fov == self.camera_fov + step {
+            self.fov_zoom - step
+        } else {
+            0.0
+        };
+        self.camera_fov = fov;
+    }
+
+    /// Moves the camera `seconds` further along the current jump.
+    fn update_camera_transition(&mut self, seconds: f32) {
+        let Some(ref mut transition) = self.camera_transition else {
+            return;
+        };
+        transition.elapsed += seconds;
+        let t = (transition.elapsed / self.camera_transition_time).min(1.0);
+        // Ease in and out.
+        let pose = transition.from.lerp(transition.to, t * t * (3.0 - 2.0 * t));
+        if t >= 1.0 {
+            self.camera_transition = None;
+        }
+        self.set_camera_pose(pose);
+    }
+
+    /// Called for every input from the window, ends the idle orbit.
+    fn note_input(&mut self) {
+        self.last_input = Instant::now();
+        if self.idle {
+            info!("Input received, leaving the idle orbit");
+            self.idle = false;
+        }
+    }
+
+    /// Starts the idle orbit once there has been no input for --idle-timeout and turns the
+    /// camera `seconds` further around the pivot while it runs.
+    fn update_idle(&mut self, now: Instant, seconds: f32) {
+        let Some(timeout) = self.idle_timeout else {
+            return;
+        };
+        if !self.idle {
+            if now - self.last_input < timeout {
+                return;
+            }
+            info!("No input for {:.0?}, starting the idle orbit", timeout);
+            self.idle = true;
+            self.face_pivot();
+        }
+        self.turn_around_pivot(seconds);
+    }
+
+    /// Looks at the pivot, which outside of orbit mode becomes the --look-at point or the point
+    /// in front of the camera.
+    fn face_pivot(&mut self) {
+        // Orbit mode already has a pivot, and keeps it when the orbit ends.
+        if !self.orbit_mode {
+            self.orbit_target = match self.look_at_target {
+                Some(target) => target,
+                None => {
+                    let (forward, _, _) = self.camera_axes();
+                    (self.camera_location + forward * ORBIT_DEFAULT_DISTANCE).into()
+                }
+            };
+        }
+        self.look_at(self.orbit_target);
+        self.orbit_radius = self.orbit_distance().max(MIN_ORBIT_DISTANCE);
+    }
+
+    /// Turns the camera around the pivot for `seconds` at --idle-orbit-speed.
+    fn turn_around_pivot(&mut self, seconds: f32) {
+        // rotate_camera turns a thousandth of a radian per pixel.
+        self.rotate_camera(DVec2::new(
+            -(self.idle_orbit_speed * seconds * 1000.0) as f64,
+            0.0,
+        ));
+        self.place_orbit_camera(self.orbit_radius);
+    }
+
+    /// Prints the frame time statistics of `times`, measured over `elapsed`.
+    fn print_frame_times(&self, times: &histogram::Histogram, elapsed: Duration) {
+        let percentiles: String = self
+            .percentiles
+            .iter()
+            .map(|&p| {
+                format!(
+                    "{}%: {:0>5.2}ms; ",
+                    p,
+                    times.percentile(p).unwrap() as f32 / 1_000.0
+                )
+            })
+            .collect();
+        println!(
+            "{:0>5} frames over {:0>5.2}s. \
+            Min: {:0>5.2}ms; \
+            Average: {:0>5.2}ms; \
+            {}\
+            Max: {:0>5.2}ms; \
+            StdDev: {:0>5.2}ms",
+            times.entries(),
+            elapsed.as_secs_f32(),
+            times.minimum().unwrap() as f32 / 1_000.0,
+            times.mean().unwrap() as f32 / 1_000.0,
+            percentiles,
+            times.maximum().unwrap() as f32 / 1_000.0,
+            times.stddev().unwrap() as f32 / 1_000.0,
+        );
+    }
+
+    /// Measures a --bench frame once the scene is loaded, and after the last one prints the
+    /// statistics of the whole run and exits.
+    fn update_bench(
+        &mut self,
+        now: Instant,
+        delta_time: Duration,
+        event_loop_window_target: &EventLoopWindowTarget<UserResizeEvent<()>>,
+    ) {
+        let Some(ref mut bench) = self.bench else {
+            return;
+        };
+        if self.loading {
+            return;
+        }
+        let Some(start) = bench.start else {
+            // The frame that finished loading isn't measured, it includes the upload.
+            bench.start = Some(now);
+            self.face_pivot();
+            return;
+        };
+        bench
+            .times
+            .increment(delta_time.as_micros() as u64)
+            .unwrap();
+        if bench.times.entries() < bench.frames as u64 {
+            self.turn_around_pivot(BENCH_TIME_STEP);
+            return;
+        }
+        let bench = self.bench.take().unwrap();
+        println!("Benchmark finished:");
+        self.print_frame_times(&bench.times, now - start);
+        event_loop_window_target.exit();
+    }
+
+    /// Ray casts from the cursor, or the middle of the window while the pointer is grabbed, into
+    /// the gltf scenes for the coordinate readout.
+    #[cfg(feature = "gltf")]
+    fn update_cursor_hit(&mut self, resolution: UVec2) {
+        if !self.show_coordinates {
+            return;
+        }
+        let cursor = if self
+            .grabber
+            .as_ref()
+            .map_or(false, |grabber| grabber.grabbed())
+        {
+            resolution.as_vec2() / 2.0
+        } else if let Some(cursor) = self.cursor_position {
+            cursor.as_vec2()
+        } else {
+            self.last_pick = None;
+            self.cursor_hit = None;
+            return;
+        };
+        let pick = (
+            cursor,
+            self.camera_location,
+            self.camera_pitch,
+            self.camera_yaw,
+            self.camera_fov,
+            self.ortho_height,
+        );
+        if self.last_pick == Some(pick) {
+            return;
+        }
+        self.last_pick = Some(pick);
+
+        self.cursor_hit = None;
+        let Some(view_part) = self.views(resolution).into_iter().find(|view_part| {
+            let offset = view_part.viewport.offset.as_vec2();
+            let local = cursor - offset;
+            local.cmpge(Vec2::ZERO).all() && local.cmplt(view_part.viewport.size.as_vec2()).all()
+        }) else {
+            return;
+        };
+        let size = view_part.viewport.size.as_vec2();
+        let view_proj =
+            projection_to_matrix(view_part.projection, size.x / size.y, self.handedness)
+                * self.view_matrix()
+                * Mat4::from_translation(-view_part.camera_offset);
+        let (origin, direction) = picking::cursor_ray(
+            cursor - view_part.viewport.offset.as_vec2(),
+            size,
+            view_proj.inverse(),
+        );
+        self.cursor_hit = self
+            .scene
+            .iter()
+            .chain(&self.compare_scene)
+            .filter_map(|scene| {
+                picking::ray_cast(origin, direction, &scene.triangles.0, &scene.triangles.1)
+            })
+            .min_by(f32::total_cmp)
+            .map(|distance| origin + direction * distance - view_part.camera_offset);
+    }
+
+    /// Moves the camera to the next --camera-stdin view once the previous one is saved, and exits
+    /// when there are no more.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn step_camera_script(
+        &mut self,
+        event_loop_window_target: &EventLoopWindowTarget<UserResizeEvent<()>>,
+    ) {
+        if self.loading || self.frame_capture.is_some() {
+            return;
+        }
+        let Some(ref mut script) = self.camera_script else {
+            return;
+        };
+        match script.next() {
+            camera_script::ScriptStep::Camera(camera, path) => {
+                self.camera_location = Vec3A::new(camera[0], camera[1], camera[2]);
+                self.camera_pitch = camera[3];
+                self.camera_yaw = camera[4];
+                self.frame_capture = Some(path);
+            }
+            camera_script::ScriptStep::Waiting => {}
+            camera_script::ScriptStep::Invalid(e) => {
+                self.error_policy
+                    .report(format_args!("Invalid camera on stdin: {}", e));
+            }
+            camera_script::ScriptStep::Finished => {
+                println!("Camera script finished");
+                event_loop_window_target.exit();
+            }
+        }
+    }
+
+    /// Holds all time-driven state where it is, or lets it run again.
+    fn set_frozen(&mut self, frozen: bool) {
+        self.frozen_time = frozen.then_some(self.scene_time);
+    }
+
+    /// Usages to configure the window surface with, given what it `supported`. Copying from the
+    /// surface is only asked for when finished frames are read back, by --crossfade,
+    /// --auto-exposure or saving frames, and those are turned off when the surface can't.
+    fn negotiate_surface_usage(&mut self, supported: wgpu::TextureUsages) -> wgpu::TextureUsages {
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST;
+        // F12 can save a frame at any time natively.
+        let reads_frames = cfg!(not(target_arch = "wasm32"))
+            || self.crossfade_duration.is_some()
+            || self.auto_exposure_speed.is_some();
+        if !reads_frames {
+            return usage;
+        }
+        if supported.contains(wgpu::TextureUsages::COPY_SRC) {
+            return usage | wgpu::TextureUsages::COPY_SRC;
+        }
+        if self.crossfade_duration.take().is_some() {
+            warn!("--crossfade needs a surface that can be copied from, ignoring");
+        }
+        if self.auto_exposure_speed.take().is_some() {
+            warn!("--auto-exposure needs a surface that can be copied from, ignoring");
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.camera_script.take().is_some() {
+            self.error_policy.report(
+                "--camera-stdin needs a surface that can be copied from to save frames, ignoring",
+            );
+        }
+        usage
+    }
+
+    /// Freezes time and saves the next frame, before the overlay is drawn. Time runs again
+    /// afterwards unless it was already frozen.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn capture_frame(&mut self) {
+        if self.frame_capture.is_some() {
+            return;
+        }
+        if !self.surface_usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            println!("This surface can't be copied from, so frames can't be saved");
+            return;
+        }
+        self.resume_after_capture = self.frozen_time.is_none();
+        self.set_frozen(true);
+        self.frame_capture = Some(format!("capture-{:04}.png", self.capture_count).into());
+        self.capture_count += 1;
+    }
+
+    /// Outline of the volume the directional light's shadow map covers, as a transform of the
+    /// cube from -1 to 1. Like rend3 does, this is an orthographic box `shadow_distance` out
+    /// from the camera on every side, facing along the light.
+    fn shadow_bounds(&self, direction: Vec3) -> Mat4 {
+        let direction = direction.normalize();
+        // The up vector only turns the box around the light direction, any that isn't parallel
+        // to it does.
+        let up = if direction.y.abs() > 0.999 {
+            Vec3::Z
+        } else {
+            Vec3::Y
+        };
+        let light_to_world = Mat4::look_at_lh(Vec3::ZERO, direction, up).inverse();
+        Mat4::from_translation(self.camera_location.into())
+            * light_to_world
+            * Mat4::from_scale(Vec3::splat(self.shadow_distance))
+    }
+
+    /// Prints how much of the scene the shadow map covers and how coarse its texels are.
+    fn print_shadow_coverage(&self) {
+        if self.directional_light_directions().is_empty() {
+            println!("No --directional-light or --sun-time light to show the shadow bounds of");
+            return;
+        }
+        let size = self.shadow_distance * 2.0;
+        println!(
+            "The directional shadow covers {:.1} units around the camera, {:.3} units per texel \
+             of its {}x{} shadow map",
+            size,
+            size / DIRECTIONAL_SHADOW_RESOLUTION as f32,
+            DIRECTIONAL_SHADOW_RESOLUTION,
+            DIRECTIONAL_SHADOW_RESOLUTION
+        );
+    }
+
+    /// Replaces last frame's debug lines with this frame's.
+    fn build_debug_lines(&mut self) {
+        let shadow_bounds: Vec<Mat4> = if self.show_shadow_bounds {
+            self.directional_light_directions()
+                .into_iter()
+                .map(|direction| self.shadow_bounds(direction))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let Some(ref mut lines) = self.debug_lines else {
+            return;
+        };
+        lines.clear();
+        for bounds in shadow_bounds {
+            lines.cuboid(bounds, SHADOW_BOUNDS_COLOR);
+        }
+        if self.orbit_mode {
+            let size = (Vec3A::from(self.orbit_target) - self.camera_location).length()
+                * ORBIT_PIVOT_MARKER_SIZE;
+            for (axis, color) in [
+                (Vec3::X, Vec4::new(1.0, 0.2, 0.2, 1.0)),
+                (Vec3::Y, Vec4::new(0.2, 1.0, 0.2, 1.0)),
+                (Vec3::Z, Vec4::new(0.3, 0.4, 1.0, 1.0)),
+            ] {
+                lines.line(
+                    self.orbit_target - axis * size,
+                    self.orbit_target + axis * size,
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Moves towards the pivot for negative steps and away from it for positive ones. While
+    /// flying, narrows or widens the field of view instead, and in orthographic mode shrinks or
+    /// grows the view.
+    fn zoom(&mut self, steps: f64) {
+        if self.projection_mode == ProjectionMode::Orthographic {
+            self.ortho_height =
+                (self.ortho_height * ZOOM_STEP_FACTOR.powf(steps as f32)).max(MIN_ORTHO_HEIGHT);
+            return;
+        }
+        if !self.orbit_mode {
+            self.fov_zoom += steps as f32 * FOV_ZOOM_STEP;
+            return;
+        }
+        let distance =
+            (self.orbit_radius * ZOOM_STEP_FACTOR.powf(steps as f32)).max(MIN_ORBIT_DISTANCE);
+        self.place_orbit_camera(distance);
+    }
+
+    /// Logs the statistics of a newly loaded scene with --stats.
+    #[cfg(feature = "gltf")]
+    fn log_stats(&self, scene: &LoadedGltf) {
+        if let (true, Some(stats)) = (self.log_stats, &scene.stats) {
+            stats.log();
+        }
+    }
+
+    /// Hides the shown scene and shows the next of the other files in its place.
+    #[cfg(feature = "gltf")]
+    fn switch_scene(&mut self, renderer: &Renderer) {
+        let Some(mut next) = self.other_scenes.pop_front() else {
+            return;
+        };
+        let nodes = next.document.as_ref().map_or_else(Vec::new, |document| {
+            document
+                .default_scene()
+                .or_else(|| document.scenes().next())
+                .map_or_else(Vec::new, |scene| scene.nodes().collect())
+        });
+        match rend3_gltf::instance_loaded_scene::<asset_fetch::FetchError>(
+            renderer,
+            &next.scene,
+            nodes,
+            &self.gltf_settings,
+            next.transform,
+        ) {
+            Ok(instance) => next.instance = Some(instance),
+            Err(e) => {
+                self.error_policy
+                    .report(format_args!("Failed to instance {}: {}", next.path, e));
+                self.other_scenes.push_front(next);
+                return;
+            }
+        }
+        if let Some(mut shown) = self.scene.take() {
+            shown.instance = None;
+            self.other_scenes.push_back(shown);
+        }
+        println!("Showing {}", next.path);
+        self.animation = None;
+        self.start_animation(&next.scene);
+        self.scene = Some(next);
+
+        self.refresh_triangles(renderer);
+    }
+
+    /// Uploads the triangles of the shown scenes to the wireframe and depth of field again, after
+    /// the shown scene changed.
+    #[cfg(feature = "gltf")]
+    fn refresh_triangles(&mut self, renderer: &Renderer) {
+        if let Some(ref mut wireframe) = self.wireframe {
+            wireframe.clear();
+        }
+        if let Some(ref mut depth_of_field) = self.depth_of_field {
+            depth_of_field.clear();
+        }
+        if let Some(scene) = self.scene.take() {
+            self.add_triangles(renderer, &scene);
+            self.scene = Some(scene);
+        }
+        if let Some(scene) = self.compare_scene.take() {
+            self.add_triangles(renderer, &scene);
+            self.compare_scene = Some(scene);
+        }
+        self.last_pick = None;
+    }
+
+    /// Removes the scene of `path` and loads it again in the background, for --watch. It comes
+    /// back as the shown scene.
+    #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+    fn reload_scene(&mut self, renderer: &Arc<Renderer>, path: String) {
+        info!("{} changed, loading it again", path);
+        self.other_scenes.retain(|scene| scene.path != path);
+        // The new scene is shown once it is loaded, in place of whichever one is shown now.
+        if let Some(mut shown) = self.scene.take() {
+            if shown.path != path {
+                shown.instance = None;
+                self.other_scenes.push_front(shown);
+            }
+        }
+        self.animation = None;
+        self.refresh_triangles(renderer);
+
+        let (load_event_sender, load_events) = mpsc::channel();
+        self.load_events = Some(load_events);
+        self.loading = true;
+        let renderer = Arc::clone(renderer);
+        let loader = asset_loader("resources/", &self.asset_url);
+        let gltf_settings = self.gltf_settings;
+        let center = self
+            .center_model
+            .then(|| self.look_at_target.unwrap_or(Vec3::ZERO));
+        let base_color_space = self.base_color_space;
+        let validate = self.validate;
+        let morph_weights = self.morph_weights.clone();
+        let error_policy = self.error_policy;
+        spawn(async move {
+            match load_gltf(
+                &renderer,
+                &loader,
+                &gltf_settings,
+                AssetPath::External(&path),
+                Mat4::IDENTITY,
+                center,
+                base_color_space,
+                validate,
+                &morph_weights,
+                &load_event_sender,
+            )
+            .await
+            {
+                Ok(loaded) => {
+                    let _ = load_event_sender.send(LoadEvent::Scene(Box::new(loaded)));
+                }
+                Err(e) => error_policy.report(e),
+            }
+            let _ = load_event_sender.send(LoadEvent::Finished);
+        });
+    }
+
+    /// Uploads the scene's triangles to the wireframe and depth of field.
+    #[cfg(feature = "gltf")]
+    fn add_triangles(&mut self, renderer: &Renderer, scene: &LoadedGltf) {
+        let (ref positions, ref indices) = scene.triangles;
+        if let Some(ref mut wireframe) = self.wireframe {
+            wireframe.add_mesh(&renderer.device, &positions, &indices);
+        }
+        if let Some(ref mut depth_of_field) = self.depth_of_field {
+            depth_of_field.add_mesh(&renderer.device, &positions, &indices);
+        }
+    }
+
+    /// Whether a newly loaded scene's materials differ from what rend3-gltf made of them.
+    #[cfg(feature = "gltf")]
+    fn materials_adjusted(&self, scene: &LoadedGltf) -> bool {
+        !self.material_adjustment.is_none()
+            || !scene.color_textures.is_empty()
+            || self.emissive_scale != 1.0
+            || scene
+                .document
+                .as_ref()
+                .map_or(false, material_override::uses_emissive_strength)
+    }
+
+    /// Applies the current material adjustment to a scene's materials.
+    #[cfg(feature = "gltf")]
+    fn adjust_materials(&self, renderer: &Renderer, scene: &LoadedGltf) {
+        if let Some(ref document) = scene.document {
+            material_override::apply(
+                renderer,
+                &scene.scene,
+                document,
+                &self.gltf_settings,
+                self.material_adjustment,
+                &scene.color_textures,
+                self.emissive_scale,
+            );
+        }
+    }
+
+    /// Changes the material adjustment by the given amounts, or resets it with `None`, and
+    /// applies it to the loaded scenes.
+    #[cfg(feature = "gltf")]
+    fn change_material_adjustment(&mut self, renderer: &Renderer, change: Option<(f32, f32)>) {
+        self.material_adjustment = match change {
+            Some((roughness, metallic)) => material_override::MaterialAdjustment {
+                roughness: (self.material_adjustment.roughness + roughness).clamp(-1.0, 1.0),
+                metallic: (self.material_adjustment.metallic + metallic).clamp(-1.0, 1.0),
+            },
+            None => material_override::MaterialAdjustment::default(),
+        };
+        for scene in self
+            .scene
+            .iter()
+            .chain(&self.compare_scene)
+            .chain(&self.other_scenes)
+        {
+            self.adjust_materials(renderer, scene);
+        }
+        println!(
+            "Material roughness {:+.1}, metallic {:+.1}",
+            self.material_adjustment.roughness, self.material_adjustment.metallic
+        );
+    }
+
+    /// Lists the scene's animations and starts playing the one from --animation.
+    #[cfg(feature = "gltf")]
+    fn start_animation(&mut self, scene: &rend3_gltf::LoadedGltfScene) {
+        let clips = animation::clip_names(scene);
+        if !clips.is_empty() {
+            info!("Animations: {}", clips.join(", "));
+        }
+        let Some(ref name) = self.animation_name else {
+            return;
+        };
+        let Some(clip) = animation::find_clip(scene, name) else {
+            self.error_policy
+                .report(format_args!("The gltf has no animation named {}", name));
+            return;
+        };
+        let blend_clip = match self.blend_animation_name {
+            Some(ref name) => match animation::find_clip(scene, name) {
+                Some(clip) => Some(clip),
+                None => {
+                    self.error_policy
+                        .report(format_args!("The gltf has no animation named {}", name));
+                    None
+                }
+            },
+            None => None,
+        };
+        self.animation = Some(animation::AnimationPlayer::new(
+            clip,
+            blend_clip,
+            self.animation_blend,
+            !self.animation_once,
+        ));
+    }
+
+    /// Switches to the next gltf camera, and from the last one back to the free camera.
+    #[cfg(feature = "gltf")]
+    fn cycle_scene_camera(&mut self) {
+        if self.scene_cameras.is_empty() {
+            println!("The gltf has no cameras");
+            return;
+        }
+        let next = match self.scene_camera {
+            None => {
+                self.free_camera = self.camera_pose();
+                Some(0)
+            }
+            Some(idx) => Some(idx + 1).filter(|&next| next < self.scene_cameras.len()),
+        };
+        self.scene_camera = next;
+        match next {
+            Some(idx) => {
+                let camera = self.scene_cameras[idx].clone();
+                self.set_camera_from_scene(&camera);
+            }
+            None => {
+                info!("Using the free camera");
+                self.set_camera_pose(self.free_camera);
+            }
+        }
+        if self.orbit_mode {
+            // Keeps orbiting around what is in front of the new camera.
+            let (forward, _, _) = self.camera_axes();
+            self.orbit_target = (self.camera_location + forward * self.orbit_radius).into();
+        }
+    }
+
+    #[cfg(feature = "gltf")]
+    fn set_camera_from_scene(&mut self, camera: &scene_info::SceneCamera) {
+        info!(
+            "Using gltf camera {}",
+            camera.name.as_deref().unwrap_or("<unnamed>")
+        );
+        self.camera_location = camera.position.into();
+        (self.camera_pitch, self.camera_yaw) =
+            pitch_yaw_towards(camera.forward * self.camera_mirror());
+        if let Some(vfov) = camera.vfov {
+            self.camera_fov = vfov;
+        }
+    }
+
+    fn view_matrix(&self) -> Mat4 {
+        let view = Mat4::from_euler(
+            glam::EulerRot::XYZ,
+            -self.camera_pitch,
+            -self.camera_yaw,
+            0.0,
+        );
+        // A no-op for right handed, see `camera_mirror`.
+        let mirror = Mat4::from_scale(self.camera_mirror());
+        mirror * view * mirror * Mat4::from_translation((-self.camera_location).into())
+    }
+
+    /// Scale that turns directions between the world and the right handed space pitch and yaw
+    /// are in. Left handed worlds are right handed ones mirrored along Z, so the camera controls
+    /// behave the same in both.
+    fn camera_mirror(&self) -> Vec3 {
+        match self.handedness {
+            Handedness::Left => Vec3::new(1.0, 1.0, -1.0),
+            Handedness::Right => Vec3::ONE,
+        }
+    }
+
+    /// Horizontal position of the --compare wipe line in pixels, if it is shown.
+    fn wipe_line(&self, width: u32) -> Option<u32> {
+        #[cfg(feature = "gltf")]
+        let shown = self.compare_mode == CompareMode::Wipe && self.compare_scene.is_some();
+        #[cfg(not(feature = "gltf"))]
+        let shown = false;
+        // Both sides keep at least a pixel so neither viewport is empty.
+        shown.then(|| {
+            ((width as f32 * self.wipe_position).round() as u32).clamp(1, width.max(2) - 1)
+        })
+    }
+
+    /// Parts of the frame to render, each with its own camera. The --compare scene is seen from
+    /// the same place relative to it as the main scene, either on the right half of the screen or
+    /// right of the wipe line.
+    fn views(&self, resolution: UVec2) -> Vec<View> {
+        let aspect_ratio = |size: UVec2| size.x as f32 / size.y as f32;
+        let whole = View {
+            viewport: rend3::graph::ViewportRect::from_size(resolution),
+            camera_offset: Vec3::ZERO,
+            projection: self.camera_projection(aspect_ratio(resolution)),
+        };
+        #[cfg(feature = "gltf")]
+        if self.compare_scene.is_some() {
+            let split = match self.compare_mode {
+                CompareMode::Split => (resolution.x / 2).max(1),
+                CompareMode::Wipe => self.wipe_line(resolution.x).unwrap(),
+                CompareMode::Off => return vec![whole],
+            };
+            let left =
+                rend3::graph::ViewportRect::new(UVec2::ZERO, UVec2::new(split, resolution.y));
+            let right = rend3::graph::ViewportRect::new(
+                UVec2::new(split, 0),
+                UVec2::new(resolution.x.saturating_sub(split).max(1), resolution.y),
+            );
+            return match self.compare_mode {
+                // Each half is a complete picture of its own.
+                CompareMode::Split => vec![
+                    View {
+                        viewport: left,
+                        camera_offset: Vec3::ZERO,
+                        projection: self.camera_projection(aspect_ratio(left.size)),
+                    },
+                    View {
+                        viewport: right,
+                        camera_offset: COMPARE_OFFSET,
+                        projection: self.camera_projection(aspect_ratio(right.size)),
+                    },
+                ],
+                // Both sides are cut out of the same full screen picture.
+                _ => {
+                    let full = self.projection_matrix(aspect_ratio(resolution));
+                    vec![
+                        View {
+                            viewport: left,
+                            camera_offset: Vec3::ZERO,
+                            projection: CameraProjection::Raw(crop_projection(
+                                full,
+                                0,
+                                split,
+                                resolution.x,
+                            )),
+                        },
+                        View {
+                            viewport: right,
+                            camera_offset: COMPARE_OFFSET,
+                            projection: CameraProjection::Raw(crop_projection(
+                                full,
+                                split,
+                                resolution.x,
+                                resolution.x,
+                            )),
+                        },
+                    ]
+                }
+            };
+        }
+        vec![whole]
+    }
+
+    /// The projection for a viewport with the given aspect ratio. rend3 only applies the aspect
+    /// ratio to perspective projections, so the orthographic width is worked out here.
+    fn camera_projection(&self, aspect_ratio: f32) -> CameraProjection {
+        match self.projection_mode {
+            ProjectionMode::Perspective => CameraProjection::Perspective {
+                vfov: self.camera_fov,
+                near: self.camera_near,
+            },
+            ProjectionMode::Orthographic => CameraProjection::Orthographic {
+                size: Vec3A::new(
+                    self.ortho_height * aspect_ratio,
+                    self.ortho_height,
+                    ORTHO_DEPTH,
+                ),
+            },
+        }
+    }
+
+    /// The projection matrix rend3 builds out of [`Self::camera_projection`].
+    fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        projection_to_matrix(
+            self.camera_projection(aspect_ratio),
+            aspect_ratio,
+            self.handedness,
+        )
+    }
+
+    /// Orthographic view height that shows things at the orbit pivot at the size the perspective
+    /// camera shows them.
+    fn matching_ortho_height(&self) -> f32 {
+        let distance = self.orbit_distance().max(MIN_ORBIT_DISTANCE);
+        (2.0 * distance * (self.camera_fov * 0.5).to_radians().tan()).max(MIN_ORTHO_HEIGHT)
+    }
+
+    /// Switches to the next of [`PRESENT_MODE_CYCLE`] the surface supports. The surface is
+    /// reconfigured with it before the next frame.
+    fn cycle_present_mode(&mut self) {
+        let current = PRESENT_MODE_CYCLE
+            .iter()
+            .position(|&mode| mode == self.present_mode)
+            .unwrap_or(0);
+        let Some(next) = (1..=PRESENT_MODE_CYCLE.len())
+            .map(|offset| PRESENT_MODE_CYCLE[(current + offset) % PRESENT_MODE_CYCLE.len()])
+            .find(|mode| self.supported_present_modes.contains(mode))
+        else {
+            return;
+        };
+        self.present_mode = next;
+        println!("Present mode: {:?}", next);
+    }
+
+    fn toggle_projection(&mut self) {
+        self.projection_mode = match self.projection_mode {
+            ProjectionMode::Perspective => {
+                self.ortho_height = self.matching_ortho_height();
+                ProjectionMode::Orthographic
+            }
+            ProjectionMode::Orthographic => ProjectionMode::Perspective,
+        };
+        println!("Projection: {:?}", self.projection_mode);
+    }
+
+    /// Nudges the internal resolution towards hitting --target-fps, given the average
+    /// frame time over the last stats interval.
+    fn adapt_render_scale(&mut self, mean_frame_time: Duration) {
+        let Some(target) = self.target_frame_time else {
+            return;
+        };
+
+        let ratio = mean_frame_time.as_secs_f32() / target.as_secs_f32();
+        // Dead zone so the scale doesn't oscillate around the target.
+        let new_scale = if ratio > 1.05 {
+            self.render_scale * 0.9
+        } else if ratio < 0.8 {
+            self.render_scale * 1.05
+        } else {
+            return;
+        }
+        .clamp(MIN_RENDER_SCALE, self.max_render_scale());
+
+        if new_scale != self.render_scale {
+            info!("Render scale {:.2}", new_scale);
+            self.render_scale = new_scale;
+        }
+    }
+
+    fn max_render_scale(&self) -> f32 {
+        if self.saving_power {
+            POWER_SAVER_RENDER_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    /// Turns saving power on or off, checking for battery power with `--power-saver auto`.
+    fn update_power_saver(&mut self, now: Instant) {
+        let saving_power = match self.power_saver {
+            PowerSaver::Off => false,
+            PowerSaver::On => true,
+            PowerSaver::Auto => {
+                if self
+                    .last_power_check
+                    .map_or(false, |last| now - last < POWER_CHECK_INTERVAL)
+                {
+                    return;
+                }
+                self.last_power_check = Some(now);
+                power::on_battery().unwrap_or(false)
+            }
+        };
+        if saving_power == self.saving_power {
+            return;
+        }
+        self.saving_power = saving_power;
+        if saving_power {
+            info!("Saving power: at most {} fps", POWER_SAVER_FPS);
+            self.render_scale = self.render_scale.min(POWER_SAVER_RENDER_SCALE);
+        } else {
+            info!("No longer saving power");
+            self.render_scale = default_render_scale();
+        }
+    }
+
+    /// Shortest time between frames from --max-fps and saving power, if any.
+    fn frame_limit(&self) -> Option<Duration> {
+        let power_saver = self
+            .saving_power
+            .then(|| Duration::from_secs_f32(1.0 / POWER_SAVER_FPS));
+        self.min_frame_time.max(power_saver)
+    }
+
+    fn record_input(&mut self, input: input_record::RecordedInput) {
+        if let Some(ref mut recorder) = self.input_recorder {
+            recorder.record(input);
+        }
+    }
+
+    /// Applies input that came either from the window or from a replay.
+    fn apply_input(
+        &mut self,
+        window: &Window,
+        renderer: &Arc<Renderer>,
+        input: input_record::RecordedInput,
+    ) {
+        match input {
+            input_record::RecordedInput::Key { scancode, pressed } => {
+                self.handle_key(window, renderer, scancode, pressed)
+            }
+            input_record::RecordedInput::MouseMotion { delta } => self.handle_mouse_motion(delta),
+            input_record::RecordedInput::DragLook { delta } => self.look(delta),
+            input_record::RecordedInput::Orbit { delta } => self.orbit(delta),
+            input_record::RecordedInput::Pan { delta } => self.pan(delta),
+            input_record::RecordedInput::Zoom { steps } => self.zoom(steps),
+            input_record::RecordedInput::LeftClick => {
+                if !self.grab_enabled || self.orbit_mode {
+                    return;
+                }
+                let grabber = self.grabber.as_mut().unwrap();
+
+                if !grabber.grabbed() {
+                    grabber.request_grab(window);
+                }
+            }
+            input_record::RecordedInput::Resize { size } => {
+                // Goes through the regular resize path once the window system applies it.
+                let _ = window.request_inner_size(winit::dpi::PhysicalSize::new(size.x, size.y));
+            }
+        }
+    }
+
+    fn handle_key(
+        &mut self,
+        window: &Window,
+        renderer: &Arc<Renderer>,
+        scancode: u32,
+        pressed: bool,
+    ) {
+        let was_pressed = self.scancode_status.insert(scancode, pressed);
+        // Toggles fire once per physical press rather than every frame they are held.
+        if pressed && was_pressed != Some(true) {
+            match scancode {
+                platform::Scancodes::F1 => {
+                    self.show_key_help = !self.show_key_help;
+                }
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::F2 => {
+                    self.show_wireframe = !self.show_wireframe;
+                }
+                platform::Scancodes::F3 => {
+                    self.show_frame_graph = !self.show_frame_graph;
+                }
+                platform::Scancodes::F5 => self.reset_tweaks(window, renderer),
+                platform::Scancodes::F7 => {
+                    self.toggle_projection();
+                    #[cfg(feature = "gltf")]
+                    {
+                        self.last_pick = None;
+                    }
+                }
+                platform::Scancodes::F9 => {
+                    self.set_frozen(self.frozen_time.is_none());
+                    println!(
+                        "{}",
+                        if self.frozen_time.is_some() {
+                            "Time frozen"
+                        } else {
+                            "Time running"
+                        }
+                    );
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                platform::Scancodes::F12 => self.capture_frame(),
+                platform::Scancodes::F4 => {
+                    self.show_shadow_bounds = !self.show_shadow_bounds;
+                    if self.show_shadow_bounds {
+                        self.print_shadow_coverage();
+                    }
+                }
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::F6 => {
+                    self.show_coordinates = !self.show_coordinates;
+                    self.last_pick = None;
+                }
+                platform::Scancodes::V => self.cycle_present_mode(),
+                platform::Scancodes::BRACKET_LEFT => self.change_ambient(-AMBIENT_STEP),
+                platform::Scancodes::BRACKET_RIGHT => self.change_ambient(AMBIENT_STEP),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::C => {
+                    self.compare_mode = self.compare_mode.next();
+                    println!("Compare view: {:?}", self.compare_mode);
+                }
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::DIGIT1 => self
+                    .change_material_adjustment(renderer, Some((-MATERIAL_ADJUSTMENT_STEP, 0.0))),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::DIGIT2 => {
+                    self.change_material_adjustment(renderer, Some((MATERIAL_ADJUSTMENT_STEP, 0.0)))
+                }
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::DIGIT3 => self
+                    .change_material_adjustment(renderer, Some((0.0, -MATERIAL_ADJUSTMENT_STEP))),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::DIGIT4 => {
+                    self.change_material_adjustment(renderer, Some((0.0, MATERIAL_ADJUSTMENT_STEP)))
+                }
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::DIGIT0 => self.change_material_adjustment(renderer, None),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::F => {
+                    let size = window.inner_size();
+                    self.frame_scene(size.width as f32 / size.height.max(1) as f32);
+                }
+                platform::Scancodes::K => match self.look_at_target {
+                    Some(target) => self.jump_camera(|viewer| viewer.look_at(target)),
+                    None => println!("No point to look at, set one with --look-at x,y,z"),
+                },
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::N => self.jump_camera(Self::cycle_scene_camera),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::TAB => self.switch_scene(renderer),
+                #[cfg(feature = "gltf")]
+                platform::Scancodes::SPACE => {
+                    self.animation_paused = !self.animation_paused;
+                    println!(
+                        "{}",
+                        if self.animation_paused {
+                            "Animation paused"
+                        } else {
+                            "Animation playing"
+                        }
+                    );
+                }
+                platform::Scancodes::B => {
+                    self.show_skybox = !self.show_skybox;
+                }
+                platform::Scancodes::I => {
+                    // Summary of the last full second, complementing the chrome trace from P
+                    self.gpu_timings.print();
+                }
+                platform::Scancodes::L => {
+                    self.set_light_gizmos_visible(renderer, !self.show_light_gizmos);
+                }
+                #[cfg(feature = "puppet")]
+                platform::Scancodes::H => {
+                    if self.inox_model.is_some() {
+                        self.puppet_enabled = !self.puppet_enabled;
+                    } else {
+                        println!("No --puppet is loaded");
+                    }
+                }
+                #[cfg(feature = "puppet")]
+                platform::Scancodes::SEMICOLON => {
+                    self.set_puppet_scale(self.puppet_scale / PUPPET_SCALE_STEP);
+                    println!("Puppet scale {:.3}", self.puppet_scale);
+                }
+                #[cfg(feature = "puppet")]
+                platform::Scancodes::QUOTE => {
+                    self.set_puppet_scale(self.puppet_scale * PUPPET_SCALE_STEP);
+                    println!("Puppet scale {:.3}", self.puppet_scale);
+                }
+                platform::Scancodes::O => {
+                    self.set_orbit_mode(window, !self.orbit_mode);
+                    println!(
+                        "{}",
+                        if self.orbit_mode {
+                            "Orbit mode, drag with the left mouse button to orbit, middle to pan and right or the wheel to zoom"
+                        } else {
+                            "Fly mode"
+                        }
+                    );
+                }
+                platform::Scancodes::G => {
+                    self.grab_enabled = !self.grab_enabled;
+                    if !self.grab_enabled {
+                        self.grabber.as_mut().unwrap().request_ungrab(window);
+                    }
+                    println!(
+                        "Pointer grab {}",
+                        if self.grab_enabled {
+                            "enabled"
+                        } else {
+                            "disabled, drag with the left mouse button to look around"
+                        }
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_mouse_motion(&mut self, delta: DVec2) {
+        // Track the reference position even while not grabbed, so the first motion after
+        // grabbing already has something to be relative to.
+        let prev = if self.absolute_mouse {
+            self.last_mouse_delta.replace(delta)
+        } else {
+            None
+        };
+
+        if !self.grabber.as_ref().unwrap().grabbed() {
+            return;
+        }
+
+        let mouse_delta = if self.absolute_mouse {
+            match prev {
+                Some(prev) => delta - prev,
+                None => return,
+            }
+        } else {
+            delta
+        };
+
+        self.look(mouse_delta * self.mouse_sensitivity);
+    }
+
+    /// Moves and turns the camera by the sticks and triggers of a connected gamepad.
+    #[cfg(feature = "gamepad")]
+    fn apply_gamepad(&mut self, seconds: f32) {
+        let Some(input) = self.gamepad.as_mut().and_then(|gamepad| gamepad.poll()) else {
+            return;
+        };
+        if input.is_idle() {
+            return;
+        }
+        self.note_input();
+        let (forward, side, up) = self.camera_axes();
+        let speed = if input.run {
+            self.run_speed
+        } else {
+            self.walk_speed
+        };
+        let offset = (forward * input.movement.y - side * input.movement.x + up * input.vertical)
+            * speed
+            * seconds;
+        self.camera_location += offset;
+        if self.orbit_mode {
+            self.orbit_target += Vec3::from(offset);
+        }
+        // Looking goes by mouse motion, where a thousandth of a radian is a pixel and up is
+        // negative.
+        let look = input.look * GAMEPAD_LOOK_SPEED * seconds * 1000.0;
+        self.look(DVec2::new(look.x as f64, -look.y as f64));
+    }
+
+    /// Moves the camera at `velocity`, or with --smooth-camera eases its velocity towards it
+    /// first. In orbit mode the pivot moves along, keeping the orbit radius.
+    fn move_camera(&mut self, velocity: Vec3A, seconds: f32) {
+        self.camera_velocity = match self.camera_damping {
+            // Framerate independent exponential approach.
+            Some(damping) => self
+                .camera_velocity
+                .lerp(velocity, 1.0 - (-damping * seconds).exp()),
+            None => velocity,
+        };
+        let offset = self.camera_velocity * seconds;
+        self.camera_location += offset;
+        if self.orbit_mode {
+            self.orbit_target += Vec3::from(offset);
+        }
+    }
+
+    fn action_pressed(&self, action: key_bindings::Action) -> bool {
+        button_pressed(&self.scancode_status, self.key_bindings[&action])
+    }
+
+    /// Turns the camera by mouse look motion, flipping it vertically with --invert-y.
+    fn look(&mut self, mouse_delta: DVec2) {
+        if self.invert_y {
+            self.rotate_camera(DVec2::new(mouse_delta.x, -mouse_delta.y));
+        } else {
+            self.rotate_camera(mouse_delta);
+        }
+    }
+
+    /// Cursor movement in window coordinates, used for orbit mode drags and for looking around
+    /// when not grabbing.
+    fn handle_cursor_moved(&mut self, position: DVec2) -> Option<input_record::RecordedInput> {
+        let previous = self.cursor_position.replace(position)?;
+        let delta = position - previous;
+        if self.orbit_mode {
+            return if self.left_mouse_down {
+                Some(input_record::RecordedInput::Orbit { delta })
+            } else if self.middle_mouse_down {
+                Some(input_record::RecordedInput::Pan { delta })
+            } else if self.right_mouse_down {
+                Some(input_record::RecordedInput::Zoom {
+                    steps: delta.y / PIXELS_PER_ZOOM_STEP,
+                })
+            } else {
+                None
+            };
+        }
+        // While grabbed the cursor stands still, and panning goes by the raw motion instead.
+        if self.middle_mouse_down && !self.grabber.as_ref().unwrap().grabbed() {
+            return Some(input_record::RecordedInput::Pan { delta });
+        }
+        if self.grab_enabled || !self.left_mouse_down {
+            return None;
+        }
+        Some(input_record::RecordedInput::DragLook { delta })
+    }
+
+    fn rotate_camera(&mut self, mouse_delta: DVec2) {
+        const TAU: f32 = std::f32::consts::PI * 2.0;
+
+        self.camera_yaw -= (mouse_delta.x / 1000.0) as f32;
+        self.camera_pitch -= (mouse_delta.y / 1000.0) as f32;
+        if self.camera_yaw < 0.0 {
+            self.camera_yaw += TAU;
+        } else if self.camera_yaw >= TAU {
+            self.camera_yaw -= TAU;
+        }
+        self.camera_pitch = self.camera_pitch.clamp(
+            -std::f32::consts::FRAC_PI_2 + 0.0001,
+            std::f32::consts::FRAC_PI_2 - 0.0001,
+        )
+    }
+
+    /// Moves the sun by the given number of hours and updates everything following it.
+    fn scrub_sun(&mut self, renderer: &Renderer, hours: f32) {
+        let Some(ref mut sun) = self.sun else {
+            return;
+        };
+        sun.time = (sun.time + hours).rem_euclid(24.0);
+        let direction = sun.light_direction();
+        let color = self.directional_light_color();
+
+        // --sun-time replaces the --directional-light ones, so this is the only light.
+        for handle in &self.directional_lights {
+            renderer.update_directional_light(
+                handle,
+                DirectionalLightChange {
+                    direction: Some(direction),
+                    color: Some(color),
+                    ..Default::default()
+                },
+            );
+        }
+        if let Some(ref light_gizmos) = self.light_gizmos {
+            light_gizmos.update(
+                renderer,
+                &self.directional_light_directions(),
+                &self.punctual_lights,
+            );
+        }
+    }
+
+    fn set_light_gizmos_visible(&mut self, renderer: &Renderer, visible: bool) {
+        self.show_light_gizmos = visible;
+        self.light_gizmos = None;
+        if !visible {
+            return;
+        }
+
+        match gizmos::LightGizmos::new(
+            renderer,
+            &self.directional_light_directions(),
+            &self.punctual_lights,
+        ) {
+            Ok(light_gizmos) => self.light_gizmos = Some(light_gizmos),
+            Err(e) => warn!("Failed to create light gizmos: {}", e),
+        }
+    }
+}
+impl rend3_framework::App for SceneViewer {
+    // Only used when rend3-framework creates the renderer, as on the web. `main` passes
+    // --handedness itself.
+    const HANDEDNESS: rend3::types::Handedness = rend3::types::Handedness::Right;
+
+    fn create_window(
+        &mut self,
+        builder: WindowBuilder,
+    ) -> Result<
+        (
+            winit::event_loop::EventLoop<UserResizeEvent<()>>,
+            winit::window::Window,
+        ),
+        winit::error::EventLoopError,
+    > {
+        profiling::scope!("creating window");
+
+        let event_loop = winit::event_loop::EventLoopBuilder::with_user_event().build()?;
+        let window = builder.build(&event_loop).expect("Could not build window");
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::WindowExtWebSys;
+
+            let canvas = window.canvas().unwrap();
+            let style = canvas.style();
+            style.set_property("width", "100%").unwrap();
+            style.set_property("height", "100%").unwrap();
+
+            web_sys::window()
+                .and_then(|win| win.document())
+                .and_then(|doc| doc.body())
+                .and_then(|body| body.append_child(&canvas).ok())
+                .expect("couldn't append canvas to document body");
+        }
+
+        Ok((event_loop, window))
+    }
+
+    fn create_iad<'a>(
+        &'a mut self,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = anyhow::Result<rend3::InstanceAdapterDevice>> + 'a>,
+    > {
+        Box::pin(async move {
+            Ok(rend3::create_iad(
+                self.desired_backend,
+                self.desired_device_name.clone(),
+                self.desired_profile,
+                Some(Features::ADDRESS_MODE_CLAMP_TO_BORDER),
+            )
+            .await?)
+        })
+    }
+
+    fn create_base_rendergraph(
+        &mut self,
+        renderer: &Arc<Renderer>,
+        spp: &rend3::ShaderPreProcessor,
+    ) -> BaseRenderGraph {
+        BaseRenderGraph::new(renderer, spp)
+    }
+
+    fn sample_count(&self) -> SampleCount {
+        self.samples
+    }
+
+    fn present_mode(&self) -> rend3::types::PresentMode {
+        self.present_mode
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.render_scale
+    }
+
+    fn setup<'a>(
+        &'a mut self,
+        _event_loop: &winit::event_loop::EventLoop<rend3_framework::UserResizeEvent<()>>,
+        window: &'a winit::window::Window,
+        renderer: &'a Arc<Renderer>,
+        routines: &'a Arc<rend3_framework::DefaultRoutines>,
+        surface_format: rend3::types::TextureFormat,
+    ) {
+        self.grabber = Some(rend3_framework::Grabber::new(window));
+        if let Some(target) = self.look_at_target {
+            self.look_at(target);
+        }
+        self.set_orbit_mode(window, self.orbit_mode);
+        self.overlay = Some(overlay::Overlay::new(&renderer.device, surface_format));
+        self.debug_lines = Some(debug_lines::DebugLines::new(
+            &renderer.device,
+            surface_format,
+            self.debug_line_width,
+        ));
+        #[cfg(feature = "gltf")]
+        {
+            self.wireframe = Some(wireframe::Wireframe::new(
+                &renderer.device,
+                surface_format,
+                self.wireframe_color,
+                self.wireframe_width,
+            ));
+        }
+        #[cfg(feature = "gltf")]
+        if let Some((focus, aperture, autofocus)) = self.depth_of_field_settings {
+            self.depth_of_field = Some(depth_of_field::DepthOfField::new(
+                &renderer.device,
+                surface_format,
+                focus,
+                aperture,
+                autofocus,
+            ));
+        }
+        if let Some(speed) = self.auto_exposure_speed {
+            self.auto_exposure = Some(auto_exposure::AutoExposure::new(
+                &renderer.device,
+                surface_format,
+                speed,
+            ));
+        }
+        if let Some(duration) = self.crossfade_duration {
+            self.crossfade = Some(crossfade::CrossFade::new(
+                &renderer.device,
+                surface_format,
+                duration,
+            ));
+        }
+
+        self.directional_lights = self
+            .directional_light_directions
+            .iter()
+            .map(|&direction| {
+                renderer.add_directional_light(DirectionalLight {
+                    color: self.directional_light_color(),
+                    intensity: self.directional_light_intensity,
+                    direction,
+                    distance: self.shadow_distance,
+                    resolution: DIRECTIONAL_SHADOW_RESOLUTION,
+                })
+            })
+            .collect();
+
+        if self
+            .punctual_lights
+            .iter()
+            .any(|light| light.spot.is_some())
+        {
+            warn!("rend3 has no spot lights, spot lights are approximated by point lights and their cones are ignored");
+        }
+        self.punctual_light_handles = self
+            .punctual_lights
+            .iter()
+            .map(|light| {
+                renderer.add_point_light(light.to_point_light(self.punctual_light_intensity))
+            })
+            .collect();
+        self.set_light_gizmos_visible(renderer, self.show_light_gizmos);
+
+        if let Some(count) = self.benchmark_scene_count {
+            match benchmark_scene::spawn_grid(renderer, count) {
+                Ok(objects) => self.benchmark_objects = objects,
+                Err(e) => self
+                    .error_policy
+                    .report(format_args!("Failed to create benchmark scene: {}", e)),
+            }
+        }
+
+        if let Some(ref path) = self.camera_log_path {
+            match camera_log::CameraLog::create(path) {
+                Ok(camera_log) => self.camera_log = Some(camera_log),
+                Err(e) => self
+                    .error_policy
+                    .report(format_args!("Failed to create camera log {}: {}", path, e)),
+            }
+        }
+        if let Some(ref path) = self.record_input_path {
+            match input_record::InputRecorder::create(path) {
+                Ok(recorder) => self.input_recorder = Some(recorder),
+                Err(e) => self.error_policy.report(format_args!(
+                    "Failed to create input recording {}: {}",
+                    path, e
+                )),
+            }
+        }
+        if let Some(ref path) = self.replay_input_path {
+            match input_record::InputReplay::load(path) {
+                Ok(replay) => self.input_replay = Some(replay),
+                Err(e) => self.error_policy.report(format_args!(
+                    "Failed to load input recording {}: {}",
+                    path, e
+                )),
+            }
         }
 
+        #[cfg(feature = "gltf")]
         let gltf_settings = self.gltf_settings;
-        let file_to_load = self.file_to_load.take();
-        let renderer = Arc::clone(renderer);
+        #[cfg(feature = "gltf")]
+        let base_color_space = self.base_color_space;
+        #[cfg(feature = "gltf")]
+        let validate = self.validate;
+        #[cfg(feature = "gltf")]
+        let morph_weights = self.morph_weights.clone();
+        #[cfg(feature = "gltf")]
+        let center = self
+            .center_model
+            .then(|| self.look_at_target.unwrap_or(Vec3::ZERO));
+        #[cfg(feature = "gltf")]
+        let files_to_load = std::mem::take(&mut self.files_to_load);
+        #[cfg(feature = "gltf")]
+        let compare_file = self.compare_file.take();
+        #[cfg(feature = "skybox")]
+        let skybox_layout = self.skybox_layout.clone();
+        #[cfg(feature = "skybox")]
         let routines = Arc::clone(routines);
-        let mut inox_renderer = inox2d_wgpu::Renderer::new(
-            &renderer.device,
-            &renderer.queue,
-            wgpu::TextureFormat::Bgra8Unorm,
-            &self.inox_model,
-            uvec2(window.inner_size().width, window.inner_size().height),
-        );
-        inox_renderer.camera.scale = Vec2::splat(0.12);
-        self.inox_renderer = Some(inox_renderer);
+        #[cfg(any(feature = "gltf", feature = "skybox"))]
+        let error_policy = self.error_policy;
+        #[cfg(any(feature = "gltf", feature = "skybox"))]
+        let asset_url = self.asset_url.clone();
+        #[cfg(any(feature = "gltf", feature = "skybox"))]
+        let renderer = Arc::clone(renderer);
+        let (load_event_sender, load_events) = mpsc::channel();
+        self.load_events = Some(load_events);
+        self.loading = true;
+        if let Some(ref mut crossfade) = self.crossfade {
+            crossfade.request_capture();
+        }
+        #[cfg(feature = "puppet")]
+        if self.inox_model.is_some() {
+            self.create_puppet_renderer(
+                &renderer,
+                uvec2(window.inner_size().width, window.inner_size().height),
+            );
 
-        let inox_texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("inox texture"),
-            size: Extent3d {
-                width: window.inner_size().width,
-                height: window.inner_size().height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Bgra8Unorm,
-            usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[wgpu::TextureFormat::Bgra8Unorm],
-        });
-        self.inox_texture = Some(inox_texture);
+            let inox_texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("inox texture"),
+                size: Extent3d {
+                    width: window.inner_size().width,
+                    height: window.inner_size().height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Bgra8Unorm,
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[wgpu::TextureFormat::Bgra8Unorm],
+            });
+            self.inox_texture = Some(inox_texture);
+        }
         spawn(async move {
-            let loader = rend3_framework::AssetLoader::new_local(
-                concat!(env!("CARGO_MANIFEST_DIR"), "/resources/"),
-                "",
-                "http://localhost:8000/resources/",
-            );
-            if let Err(e) = load_skybox(&renderer, &loader, &routines.skybox).await {
-                println!("Failed to load skybox {}", e)
+            #[cfg(any(feature = "gltf", feature = "skybox"))]
+            let loader = asset_loader("resources/", &asset_url);
+            #[cfg(feature = "skybox")]
+            if let Some(ref skybox_layout) = skybox_layout {
+                if let Err(e) =
+                    load_skybox(&renderer, &loader, &routines.skybox, skybox_layout).await
+                {
+                    error_policy.report(format_args!("Failed to load skybox {}", e));
+                };
+            }
+            // Without a file there is nothing to load, the viewer just shows an empty scene.
+            #[cfg(feature = "gltf")]
+            let mut files_to_load = files_to_load.into_iter();
+            #[cfg(feature = "gltf")]
+            let loaded = match files_to_load.next() {
+                Some(path) => {
+                    match load_gltf(
+                        &renderer,
+                        &loader,
+                        &gltf_settings,
+                        AssetPath::External(&path),
+                        Mat4::IDENTITY,
+                        center,
+                        base_color_space,
+                        validate,
+                        &morph_weights,
+                        &load_event_sender,
+                    )
+                    .await
+                    {
+                        Ok(loaded) => Some(loaded),
+                        Err(e) => {
+                            error_policy.report(e);
+                            None
+                        }
+                    }
+                }
+                None => None,
             };
-            Box::leak(Box::new(
-                load_gltf(
+            // The event loop may be gone already if the window was closed mid load, in which case
+            // the scene is simply dropped here.
+            #[cfg(feature = "gltf")]
+            if let Some(loaded) = loaded {
+                let _ = load_event_sender.send(LoadEvent::Scene(Box::new(loaded)));
+            }
+            #[cfg(feature = "gltf")]
+            for path in files_to_load {
+                match load_gltf(
                     &renderer,
                     &loader,
                     &gltf_settings,
-                    file_to_load.as_deref().map_or_else(
-                        || AssetPath::Internal("default-scene/scene.gltf"),
-                        AssetPath::External,
-                    ),
+                    AssetPath::External(&path),
+                    Mat4::IDENTITY,
+                    center,
+                    base_color_space,
+                    validate,
+                    &morph_weights,
+                    &load_event_sender,
                 )
-                .await,
-            ));
+                .await
+                {
+                    Ok(loaded) => {
+                        let _ = load_event_sender.send(LoadEvent::OtherScene(Box::new(loaded)));
+                    }
+                    Err(e) => error_policy.report(e),
+                }
+            }
+            #[cfg(feature = "gltf")]
+            if let Some(path) = compare_file {
+                match load_gltf(
+                    &renderer,
+                    &loader,
+                    &gltf_settings,
+                    AssetPath::External(&path),
+                    Mat4::from_translation(COMPARE_OFFSET),
+                    center,
+                    base_color_space,
+                    validate,
+                    &morph_weights,
+                    &load_event_sender,
+                )
+                .await
+                {
+                    Ok(loaded) => {
+                        let _ = load_event_sender.send(LoadEvent::CompareScene(Box::new(loaded)));
+                    }
+                    Err(e) => error_policy.report(e),
+                }
+            }
+            let _ = load_event_sender.send(LoadEvent::Finished);
         });
     }
 
@@ -641,76 +4763,163 @@ impl rend3_framework::App for SceneViewer {
                 profiling::scope!("MainEventsCleared");
                 let now = Instant::now();
 
+                self.update_power_saver(now);
+                if let Some(frame_limit) = self.frame_limit() {
+                    if now < self.next_frame {
+                        event_loop_window_target.set_control_flow(
+                            winit::event_loop::ControlFlow::WaitUntil(self.next_frame),
+                        );
+                        return;
+                    }
+                    // Catches up on late frames without rushing the ones after them.
+                    self.next_frame = (self.next_frame + frame_limit).max(now);
+                    event_loop_window_target.set_control_flow(winit::event_loop::ControlFlow::Poll);
+                }
+
+                self.poll_load_events(renderer, resolution);
+                #[cfg(all(feature = "gltf", not(target_arch = "wasm32")))]
+                if !self.loading {
+                    if let Some((ref path, ref mut watch)) = self.file_watch {
+                        if watch.poll(now) {
+                            let path = path.clone();
+                            self.reload_scene(renderer, path);
+                        }
+                    }
+                }
+                #[cfg(all(feature = "puppet", not(target_arch = "wasm32")))]
+                if let Some((ref path, ref mut watch)) = self.puppet_watch {
+                    if watch.poll(now) {
+                        let path = path.clone();
+                        let size = window.inner_size();
+                        self.reload_puppet(renderer, &path, uvec2(size.width, size.height));
+                    }
+                }
+
+                if let Some(ref mut replay) = self.input_replay {
+                    let due = replay.due();
+                    let finished = replay.is_finished();
+                    for input in due {
+                        self.apply_input(window, renderer, input);
+                    }
+                    if finished {
+                        println!("Input replay finished");
+                        self.input_replay = None;
+                    }
+                }
+
                 let delta_time = now - self.timestamp_last_frame;
                 self.frame_times
                     .increment(delta_time.as_micros() as u64)
                     .unwrap();
+                self.frame_graph.push(delta_time);
 
                 let elapsed_since_second = now - self.timestamp_last_second;
-                if elapsed_since_second > Duration::from_secs(1) {
-                    let count = self.frame_times.entries();
-                    println!(
-                        "{:0>5} frames over {:0>5.2}s. \
-                        Min: {:0>5.2}ms; \
-                        Average: {:0>5.2}ms; \
-                        95%: {:0>5.2}ms; \
-                        99%: {:0>5.2}ms; \
-                        Max: {:0>5.2}ms; \
-                        StdDev: {:0>5.2}ms",
-                        count,
-                        elapsed_since_second.as_secs_f32(),
-                        self.frame_times.minimum().unwrap() as f32 / 1_000.0,
-                        self.frame_times.mean().unwrap() as f32 / 1_000.0,
-                        self.frame_times.percentile(95.0).unwrap() as f32 / 1_000.0,
-                        self.frame_times.percentile(99.0).unwrap() as f32 / 1_000.0,
-                        self.frame_times.maximum().unwrap() as f32 / 1_000.0,
-                        self.frame_times.stddev().unwrap() as f32 / 1_000.0,
-                    );
+                if elapsed_since_second > self.stats_interval {
+                    self.print_frame_times(&self.frame_times, elapsed_since_second);
+                    self.adapt_render_scale(Duration::from_micros(
+                        self.frame_times.mean().unwrap(),
+                    ));
                     self.timestamp_last_second = now;
                     self.frame_times.clear();
+                    self.gpu_timings.roll();
                 }
 
                 self.timestamp_last_frame = now;
+                self.update_bench(now, delta_time, event_loop_window_target);
+                // Time-driven state advances by this, which stays at zero while frozen. Benchmarks
+                // take fixed steps so every run shows the same frames.
+                let scene_seconds = match (self.frozen_time, &self.bench) {
+                    (Some(_), _) => 0.0,
+                    (None, Some(_)) => BENCH_TIME_STEP,
+                    (None, None) => delta_time.as_secs_f32(),
+                };
+                self.scene_time += scene_seconds;
+                self.update_idle(now, scene_seconds);
+                self.update_camera_transition(delta_time.as_secs_f32());
+                self.update_fov_zoom(delta_time.as_secs_f32());
 
-                let rotation = Mat3A::from_euler(
-                    glam::EulerRot::XYZ,
-                    -self.camera_pitch,
-                    -self.camera_yaw,
-                    0.0,
-                )
-                .transpose();
-                let forward = -rotation.z_axis;
-                let up = rotation.y_axis;
-                let side = -rotation.x_axis;
-                let velocity = if button_pressed(&self.scancode_status, platform::Scancodes::SHIFT)
-                {
+                let (forward, side, up) = self.camera_axes();
+                let velocity = if self.action_pressed(key_bindings::Action::Run) {
                     self.run_speed
                 } else {
                     self.walk_speed
                 };
-                if button_pressed(&self.scancode_status, platform::Scancodes::W) {
-                    self.camera_location += forward * velocity * delta_time.as_secs_f32();
+                let mut direction = Vec3A::ZERO;
+                if self.action_pressed(key_bindings::Action::Forward) {
+                    direction += forward;
                 }
-                if button_pressed(&self.scancode_status, platform::Scancodes::S) {
-                    self.camera_location -= forward * velocity * delta_time.as_secs_f32();
+                if self.action_pressed(key_bindings::Action::Back) {
+                    direction -= forward;
                 }
-                if button_pressed(&self.scancode_status, platform::Scancodes::A) {
-                    self.camera_location += side * velocity * delta_time.as_secs_f32();
+                if self.action_pressed(key_bindings::Action::Left) {
+                    direction += side;
+                }
+                if self.action_pressed(key_bindings::Action::Right) {
+                    direction -= side;
+                }
+                if self.action_pressed(key_bindings::Action::Up) {
+                    direction += up;
+                }
+                self.move_camera(direction * velocity, delta_time.as_secs_f32());
+                #[cfg(feature = "gamepad")]
+                self.apply_gamepad(delta_time.as_secs_f32());
+                let mut sun_scrub = 0.0;
+                if button_pressed(&self.scancode_status, platform::Scancodes::T) {
+                    sun_scrub -= 1.0;
+                }
+                if button_pressed(&self.scancode_status, platform::Scancodes::Y) {
+                    sun_scrub += 1.0;
+                }
+                let mut sun_hours =
+                    sun_scrub * SUN_SCRUB_HOURS_PER_SECOND * delta_time.as_secs_f32();
+                if let Some(daycycle) = self.daycycle {
+                    sun_hours += 24.0 * scene_seconds / daycycle;
+                }
+                if sun_hours != 0.0 {
+                    self.scrub_sun(renderer, sun_hours);
+                }
+                #[cfg(feature = "gltf")]
+                if let (Some(ref mut player), Some(ref scene)) = (&mut self.animation, &self.scene)
+                {
+                    if player.has_blend_clip() {
+                        let mut blend = 0.0;
+                        if button_pressed(&self.scancode_status, platform::Scancodes::MINUS) {
+                            blend -= 1.0;
+                        }
+                        if button_pressed(&self.scancode_status, platform::Scancodes::EQUAL) {
+                            blend += 1.0;
+                        }
+                        if blend != 0.0 {
+                            player.blend = (player.blend
+                                + blend * ANIMATION_BLEND_PER_SECOND * delta_time.as_secs_f32())
+                            .clamp(0.0, 1.0);
+                        }
+                    }
+                    if !self.animation_paused {
+                        player.advance(&scene.scene, scene_seconds);
+                    }
+                    if let Some(ref instance) = scene.instance {
+                        player.pose(renderer, &scene.scene, instance);
+                    }
                 }
-                if button_pressed(&self.scancode_status, platform::Scancodes::D) {
-                    self.camera_location -= side * velocity * delta_time.as_secs_f32();
+                // Lets readbacks, like the auto exposure and autofocus ones, finish.
+                renderer.device.poll(wgpu::Maintain::Poll);
+                if let Some(ref mut auto_exposure) = self.auto_exposure {
+                    auto_exposure.update(scene_seconds);
                 }
-                if button_pressed(&self.scancode_status, platform::Scancodes::Q) {
-                    self.camera_location += up * velocity * delta_time.as_secs_f32();
+                #[cfg(feature = "gltf")]
+                if let Some(ref mut depth_of_field) = self.depth_of_field {
+                    depth_of_field.update(scene_seconds);
                 }
                 if button_pressed(&self.scancode_status, platform::Scancodes::PERIOD) {
                     println!(
-                        "{x},{y},{z},{pitch},{yaw}",
+                        "{x},{y},{z},{pitch},{yaw} --fov {fov}",
                         x = self.camera_location.x,
                         y = self.camera_location.y,
                         z = self.camera_location.z,
                         pitch = self.camera_pitch,
-                        yaw = self.camera_yaw
+                        yaw = self.camera_yaw,
+                        fov = self.camera_fov
                     );
                 }
 
@@ -732,27 +4941,37 @@ impl rend3_framework::App for SceneViewer {
                     }
                 }
 
+                #[cfg(feature = "gltf")]
+                self.update_cursor_hit(resolution);
+                #[cfg(not(target_arch = "wasm32"))]
+                self.step_camera_script(event_loop_window_target);
+                self.build_debug_lines();
+
+                // Redraws are requested continuously. With fifo the acquire in the redraw blocks until
+                // vblank, and with mailbox frames that finish between vblanks replace each other, so
+                // both end up presenting at the display's refresh rate without tearing.
                 window.request_redraw()
             }
             Event::WindowEvent {
                 event: winit::event::WindowEvent::RedrawRequested,
                 ..
             } => {
-                let view = Mat4::from_euler(
-                    glam::EulerRot::XYZ,
-                    -self.camera_pitch,
-                    -self.camera_yaw,
-                    0.0,
-                );
-                let view = view * Mat4::from_translation((-self.camera_location).into());
+                let view = self.view_matrix();
 
-                renderer.set_camera_data(Camera {
-                    projection: CameraProjection::Perspective {
-                        vfov: 60.0,
-                        near: 0.1,
-                    },
-                    view,
-                });
+                let projection = self.projection_matrix(resolution.x as f32 / resolution.y as f32);
+                if let Some(ref mut camera_log) = self.camera_log {
+                    if let Err(e) = camera_log.write(
+                        self.camera_location,
+                        self.camera_pitch,
+                        self.camera_yaw,
+                        self.camera_fov,
+                        &view,
+                        &projection,
+                    ) {
+                        self.error_policy
+                            .report(format_args!("Failed to write camera log: {}", e));
+                    }
+                }
                 /*
 
                 */
@@ -763,76 +4982,173 @@ impl rend3_framework::App for SceneViewer {
                 let mut skybox_routine = lock(&routines.skybox);
                 let tonemapping_routine = lock(&routines.tonemapping);
 
-                // Swap the instruction buffers so that our frame's changes can be processed.
-                renderer.swap_instruction_buffers();
-                // Evaluate our frame's world-change instructions
-                let mut eval_output = renderer.evaluate_instructions();
-                // Evaluate changes to routines.
-                skybox_routine.evaluate(renderer);
-
-                // Build a rendergraph
-                let mut graph = rend3::graph::RenderGraph::new();
-
-                let frame_handle = graph.add_imported_render_target(
-                    &frame,
-                    0..1,
-                    0..1,
-                    rend3::graph::ViewportRect::from_size(resolution),
-                );
-                // Add the default rendergraph
-                /*
-                                base_rendergraph.add_to_graph(
-                                    &mut graph,
-                                    &eval_output,
-                                    &pbr_routine,
-                                    Some(&skybox_routine),
-                                    &tonemapping_routine,
-                                    frame_handle,
-                                    resolution,
-                                    self.samples,
-                                    Vec3::splat(self.ambient_light_level).extend(1.0),
-                                    glam::Vec4::new(0.0, 0.0, 0.0, 1.0),
-                                );
-                */
-                base_rendergraph.add_to_graph(
-                    &mut graph,
-                    rend3_routine::base::BaseRenderGraphInputs {
-                        eval_output: &eval_output,
-                        routines: rend3_routine::base::BaseRenderGraphRoutines {
-                            pbr: &pbr_routine,
-                            skybox: Some(&skybox_routine),
-                            tonemapping: &tonemapping_routine,
+                let frame_view = frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // rend3 has a single camera, so every view is a whole frame's worth of evaluating
+                // and rendering with the camera moved, drawn into its own part of the frame.
+                for (index, view_part) in self.views(resolution).into_iter().enumerate() {
+                    let viewport = view_part.viewport;
+                    renderer.set_camera_data(Camera {
+                        projection: view_part.projection,
+                        view: view * Mat4::from_translation(-view_part.camera_offset),
+                    });
+                    renderer.set_aspect_ratio(viewport.size.x as f32 / viewport.size.y as f32);
+
+                    // Swap the instruction buffers so that our frame's changes can be processed.
+                    renderer.swap_instruction_buffers();
+                    // Evaluate our frame's world-change instructions
+                    let mut eval_output = renderer.evaluate_instructions();
+                    // Evaluate changes to routines.
+                    skybox_routine.evaluate(renderer);
+
+                    // Build a rendergraph
+                    let mut graph = rend3::graph::RenderGraph::new();
+
+                    let frame_handle =
+                        graph.add_imported_render_target(&frame, 0..1, 0..1, viewport);
+                    // Add the default rendergraph
+                    /*
+                                    base_rendergraph.add_to_graph(
+                                        &mut graph,
+                                        &eval_output,
+                                        &pbr_routine,
+                                        Some(&skybox_routine),
+                                        &tonemapping_routine,
+                                        frame_handle,
+                                        resolution,
+                                        self.samples,
+                                        Vec3::splat(self.ambient_light_level).extend(1.0),
+                                        glam::Vec4::new(0.0, 0.0, 0.0, 1.0),
+                                    );
+                    */
+                    base_rendergraph.add_to_graph(
+                        &mut graph,
+                        rend3_routine::base::BaseRenderGraphInputs {
+                            eval_output: &eval_output,
+                            routines: rend3_routine::base::BaseRenderGraphRoutines {
+                                pbr: &pbr_routine,
+                                // The routine stays alive while hidden so toggling back is instant.
+                                skybox: self.show_skybox.then_some(&*skybox_routine),
+                                tonemapping: &tonemapping_routine,
+                            },
+                            target: rend3_routine::base::OutputRenderTarget {
+                                handle: frame_handle,
+                                // Internal buffers, the tonemapping pass scales up into the frame.
+                                resolution: (viewport.size.as_vec2() * self.render_scale)
+                                    .as_uvec2()
+                                    .max(UVec2::ONE),
+                                samples: self.samples,
+                            },
                         },
-                        target: rend3_routine::base::OutputRenderTarget {
-                            handle: frame_handle,
-                            resolution,
-                            samples: self.samples,
+                        rend3_routine::base::BaseRenderGraphSettings {
+                            ambient_color: (self.ambient_color * self.ambient_light_level())
+                                .extend(1.0),
+                            clear_color: self.clear_color,
                         },
-                    },
-                    rend3_routine::base::BaseRenderGraphSettings {
-                        ambient_color: Vec3::splat(self.ambient_light_level).extend(1.0),
-                        clear_color: glam::Vec4::new(0.0, 0.0, 0.0, 1.0),
-                    },
-                );
-                // Dispatch a render using the built up rendergraph!
-                self.previous_profiling_stats = graph.execute(renderer, &mut eval_output);
+                    );
+                    // Dispatch a render using the built up rendergraph!
+                    let stats = graph.execute(renderer, &mut eval_output);
+                    // Timings stay comparable between split and single views by only counting the first.
+                    if index == 0 {
+                        self.previous_profiling_stats = stats;
+                        if let Some(ref stats) = self.previous_profiling_stats {
+                            self.gpu_timings.add_frame(stats);
+                        }
+                    }
+
+                    #[cfg(feature = "gltf")]
+                    if let Some(ref mut depth_of_field) = self.depth_of_field {
+                        let projection = projection_to_matrix(
+                            view_part.projection,
+                            viewport.size.x as f32 / viewport.size.y as f32,
+                            self.handedness,
+                        );
+                        depth_of_field.render(
+                            &renderer.device,
+                            &renderer.queue,
+                            &frame.texture,
+                            &frame_view,
+                            viewport,
+                            projection,
+                            projection * view * Mat4::from_translation(-view_part.camera_offset),
+                            index == 0,
+                        );
+                    }
+
+                    #[cfg(feature = "gltf")]
+                    if let (true, Some(ref mut wireframe)) =
+                        (self.show_wireframe, &mut self.wireframe)
+                    {
+                        let projection = projection_to_matrix(
+                            view_part.projection,
+                            viewport.size.x as f32 / viewport.size.y as f32,
+                            self.handedness,
+                        );
+                        wireframe.render(
+                            &renderer.device,
+                            &renderer.queue,
+                            &frame_view,
+                            resolution,
+                            viewport,
+                            projection * view * Mat4::from_translation(-view_part.camera_offset),
+                        );
+                    }
+
+                    if let Some(ref mut debug_lines) = self.debug_lines {
+                        let projection = projection_to_matrix(
+                            view_part.projection,
+                            viewport.size.x as f32 / viewport.size.y as f32,
+                            self.handedness,
+                        );
+                        debug_lines.render(
+                            &renderer.device,
+                            &renderer.queue,
+                            &frame_view,
+                            viewport,
+                            projection * view * Mat4::from_translation(-view_part.camera_offset),
+                        );
+                    }
+                }
+
+                if let Some(ref mut auto_exposure) = self.auto_exposure {
+                    auto_exposure.render(
+                        &renderer.device,
+                        &renderer.queue,
+                        &frame.texture,
+                        &frame_view,
+                    );
+                }
 
+                #[cfg(feature = "puppet")]
+                let puppet_head = self.puppet_head(resolution);
+                #[cfg(feature = "puppet")]
+                if let (true, Some(ref mut inox_model)) =
+                    (self.puppet_enabled, &mut self.inox_model)
                 {
-                    let puppet = &mut self.inox_model.puppet;
+                    let puppet = &mut inox_model.puppet;
                     puppet.begin_set_params();
-                    let t = self.timestamp_start.elapsed().as_secs_f32();
-                    puppet.set_param("Head:: Yaw-Pitch", vec2(t.cos(), t.sin()));
+                    puppet.set_param("Head:: Yaw-Pitch", puppet_head);
+                    for (name, value) in &self.puppet_params {
+                        puppet.set_param(name, *value);
+                    }
                     puppet.end_set_params();
                 }
-                if let Some(ref mut inox_texture) = self.inox_texture {
+                #[cfg(feature = "puppet")]
+                if let (true, Some(ref mut inox_texture)) =
+                    (self.puppet_enabled, &mut self.inox_texture)
+                {
                     let temp_view =
                         inox_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-                    if let Some(ref mut ir) = self.inox_renderer {
+                    if let (Some(ir), Some(inox_model)) =
+                        (&mut self.inox_renderer, &self.inox_model)
+                    {
                         ir.render(
                             &renderer.queue,
                             &renderer.device,
-                            &self.inox_model.puppet,
+                            &inox_model.puppet,
                             &temp_view,
                         )
                     };
@@ -853,6 +5169,85 @@ impl rend3_framework::App for SceneViewer {
                                             renderer.queue.submit(std::iter::once(encoder.finish()));
                     */
                 }
+                if let Some(ref mut crossfade) = self.crossfade {
+                    crossfade.render(&renderer.device, &renderer.queue, &frame_view);
+                }
+                // Saved before the overlay, so it has the scene without any of the HUD.
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(path) = self.frame_capture.take() {
+                    let saved = headless::read_back(
+                        renderer,
+                        &frame.texture,
+                        UVec2::new(frame.texture.width(), frame.texture.height()),
+                    )
+                    .and_then(|image| Ok(image.save(&path)?));
+                    match saved {
+                        Ok(()) => println!("Saved {}", path.display()),
+                        Err(e) => self.error_policy.report(format_args!(
+                            "Failed to save {}: {}",
+                            path.display(),
+                            e
+                        )),
+                    }
+                    if std::mem::take(&mut self.resume_after_capture) {
+                        self.set_frozen(false);
+                    }
+                }
+                if let Some(ref mut overlay) = self.overlay {
+                    if self.loading {
+                        let screen = resolution.as_vec2();
+                        overlay.rect(Vec2::ZERO, screen, LOADING_BACKGROUND);
+                        let text = match self.download_progress {
+                            (0, _) => LOADING_TEXT.to_owned(),
+                            (received, 0) => {
+                                format!("{}\n{:.1} MB", LOADING_TEXT, received as f64 / 1e6)
+                            }
+                            (received, expected) => format!(
+                                "{}\n{:.1} / {:.1} MB",
+                                LOADING_TEXT,
+                                received as f64 / 1e6,
+                                expected as f64 / 1e6
+                            ),
+                        };
+                        let size = overlay::Overlay::text_size(&text, LOADING_TEXT_SCALE);
+                        overlay.text(
+                            ((screen - size) / 2.0).round(),
+                            LOADING_TEXT_SCALE,
+                            Vec4::ONE,
+                            &text,
+                        );
+                    }
+                    if self.show_frame_graph {
+                        self.frame_graph.draw(overlay, resolution.y as f32);
+                    }
+                    if let Some(line) = self.wipe_line(resolution.x) {
+                        overlay.rect(
+                            Vec2::new(line as f32 - 1.0, 0.0),
+                            Vec2::new(line as f32 + 1.0, resolution.y as f32),
+                            Vec4::new(1.0, 1.0, 1.0, 0.8),
+                        );
+                    }
+                    #[cfg(feature = "gltf")]
+                    if let (true, Some((cursor, ..))) = (self.show_coordinates, self.last_pick) {
+                        let text = match self.cursor_hit {
+                            Some(hit) => format!("{:.3}, {:.3}, {:.3}", hit.x, hit.y, hit.z),
+                            None => "-".to_owned(),
+                        };
+                        overlay.text(
+                            (cursor + Vec2::splat(16.0)).round(),
+                            COORDINATE_TEXT_SCALE,
+                            Vec4::ONE,
+                            &text,
+                        );
+                    }
+                    if self.show_key_help {
+                        key_help::draw(overlay, resolution.as_vec2());
+                    }
+                    overlay.render(&renderer.device, &renderer.queue, &frame_view, resolution);
+                }
+                if let Some(ref mut crossfade) = self.crossfade {
+                    crossfade.capture(&renderer.device, &renderer.queue, &frame.texture);
+                }
                 frame.present();
                 // mark the end of the frame for tracy/other profilers
                 profiling::finish_frame!();
@@ -888,28 +5283,96 @@ impl rend3_framework::App for SceneViewer {
                     0
                 };
                 log::info!("WE scancode {:x}", scancode);
-                self.scancode_status.insert(
+                self.note_input();
+                if self.input_replay.is_some() {
+                    return;
+                }
+                let input = input_record::RecordedInput::Key {
                     scancode,
-                    match state {
+                    pressed: match state {
                         ElementState::Pressed => true,
                         ElementState::Released => false,
                     },
-                );
+                };
+                self.record_input(input);
+                self.apply_input(window, renderer, input);
             }
 
             Event::WindowEvent {
                 event:
                     WindowEvent::MouseInput {
                         button: MouseButton::Left,
-                        state: ElementState::Pressed,
+                        state,
                         ..
                     },
                 ..
             } => {
-                let grabber = self.grabber.as_mut().unwrap();
-
-                if !grabber.grabbed() {
-                    grabber.request_grab(window);
+                self.note_input();
+                self.left_mouse_down = state == ElementState::Pressed;
+                self.dragging_wipe = false;
+                // Presses on the wipe line drag it instead of looking around or grabbing the pointer.
+                if let (true, Some(line), Some(cursor)) = (
+                    self.left_mouse_down,
+                    self.wipe_line(window.inner_size().width),
+                    self.cursor_position,
+                ) {
+                    if (cursor.x - line as f64).abs() <= WIPE_GRAB_DISTANCE {
+                        self.dragging_wipe = true;
+                        return;
+                    }
+                }
+                if self.input_replay.is_some() || !self.left_mouse_down {
+                    return;
+                }
+                self.record_input(input_record::RecordedInput::LeftClick);
+                self.apply_input(window, renderer, input_record::RecordedInput::LeftClick);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { button, state, .. },
+                ..
+            } => {
+                self.note_input();
+                match button {
+                    MouseButton::Middle => self.middle_mouse_down = state == ElementState::Pressed,
+                    MouseButton::Right => self.right_mouse_down = state == ElementState::Pressed,
+                    _ => {}
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                self.note_input();
+                if self.input_replay.is_some() {
+                    return;
+                }
+                // Scrolling up zooms in.
+                let steps = match delta {
+                    MouseScrollDelta::LineDelta(_, lines) => -lines as f64,
+                    MouseScrollDelta::PixelDelta(pixels) => -pixels.y / PIXELS_PER_ZOOM_STEP,
+                };
+                let input = input_record::RecordedInput::Zoom { steps };
+                self.record_input(input);
+                self.apply_input(window, renderer, input);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                self.note_input();
+                if self.dragging_wipe {
+                    let width = window.inner_size().width.max(1) as f64;
+                    self.wipe_position = (position.x / width).clamp(0.0, 1.0) as f32;
+                    self.cursor_position = Some(DVec2::new(position.x, position.y));
+                    return;
+                }
+                let drag = self.handle_cursor_moved(DVec2::new(position.x, position.y));
+                if let Some(input) = drag {
+                    if self.input_replay.is_some() {
+                        return;
+                    }
+                    self.record_input(input);
+                    self.apply_input(window, renderer, input);
                 }
             }
             Event::DeviceEvent {
@@ -920,34 +5383,30 @@ impl rend3_framework::App for SceneViewer {
                     },
                 ..
             } => {
-                if !self.grabber.as_ref().unwrap().grabbed() {
+                self.note_input();
+                if self.input_replay.is_some() {
                     return;
                 }
-
-                const TAU: f32 = std::f32::consts::PI * 2.0;
-
-                let mouse_delta = if self.absolute_mouse {
-                    let prev = self.last_mouse_delta.replace(DVec2::new(delta_x, delta_y));
-                    if let Some(prev) = prev {
-                        (DVec2::new(delta_x, delta_y) - prev) / 4.0
-                    } else {
-                        return;
-                    }
+                let delta = DVec2::new(delta_x, delta_y);
+                // Absolute motion needs the previous position, which only looking keeps track of.
+                let input = if self.middle_mouse_down
+                    && !self.absolute_mouse
+                    && self.grabber.as_ref().unwrap().grabbed()
+                {
+                    input_record::RecordedInput::Pan { delta }
                 } else {
-                    DVec2::new(delta_x, delta_y)
+                    input_record::RecordedInput::MouseMotion { delta }
                 };
-
-                self.camera_yaw -= (mouse_delta.x / 1000.0) as f32;
-                self.camera_pitch -= (mouse_delta.y / 1000.0) as f32;
-                if self.camera_yaw < 0.0 {
-                    self.camera_yaw += TAU;
-                } else if self.camera_yaw >= TAU {
-                    self.camera_yaw -= TAU;
-                }
-                self.camera_pitch = self.camera_pitch.clamp(
-                    -std::f32::consts::FRAC_PI_2 + 0.0001,
-                    std::f32::consts::FRAC_PI_2 - 0.0001,
-                )
+                self.record_input(input);
+                self.apply_input(window, renderer, input);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                ..
+            } => {
+                self.record_input(input_record::RecordedInput::Resize {
+                    size: UVec2::new(size.width, size.height),
+                });
             }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
@@ -959,11 +5418,107 @@ impl rend3_framework::App for SceneViewer {
         }
     }
 }
+
+/// Present modes --low-latency tries in order. Mailbox doesn't tear and doesn't wait, immediate
+/// doesn't wait but tears, and fifo is the only one every surface supports.
+const LOW_LATENCY_PRESENT_MODES: [wgpu::PresentMode; 3] = [
+    wgpu::PresentMode::Mailbox,
+    wgpu::PresentMode::Immediate,
+    wgpu::PresentMode::Fifo,
+];
+
+/// Order V cycles through the present modes in, skipping the ones the surface doesn't support.
+const PRESENT_MODE_CYCLE: [wgpu::PresentMode; 4] = [
+    wgpu::PresentMode::Immediate,
+    wgpu::PresentMode::Fifo,
+    wgpu::PresentMode::Mailbox,
+    wgpu::PresentMode::FifoRelaxed,
+];
+
+/// The first of `preferred` the surface supports, or fifo if none of them are.
+fn negotiate_present_mode(
+    preferred: &[wgpu::PresentMode],
+    supported: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    let negotiated = match preferred.iter().find(|mode| supported.contains(mode)) {
+        Some(&mode) => mode,
+        None => {
+            warn!(
+                "Present modes {:?} are not supported by this surface (supported: {:?}), falling back to Fifo",
+                preferred, supported
+            );
+            wgpu::PresentMode::Fifo
+        }
+    };
+    info!("Using present mode {:?}", negotiated);
+    negotiated
+}
+
+/// `requested` if the surface supports it, auto otherwise.
+fn negotiate_alpha_mode(
+    requested: wgpu::CompositeAlphaMode,
+    supported: &[wgpu::CompositeAlphaMode],
+) -> wgpu::CompositeAlphaMode {
+    if requested == wgpu::CompositeAlphaMode::Auto || supported.contains(&requested) {
+        info!("Using alpha mode {:?}", requested);
+        return requested;
+    }
+    warn!(
+        "Alpha mode {:?} is not supported by this surface (supported: {:?}), falling back to Auto",
+        requested, supported
+    );
+    wgpu::CompositeAlphaMode::Auto
+}
+
+/// `requested`, or 1 if the adapter can't multisample rend3's render targets.
+fn negotiate_sample_count(requested: SampleCount, adapter: &wgpu::Adapter) -> SampleCount {
+    let mut samples = requested;
+    let supported = [TextureFormat::Rgba16Float, TextureFormat::Depth32Float]
+        .into_iter()
+        .all(|format| {
+            adapter
+                .get_texture_format_features(format)
+                .flags
+                .sample_count_supported(samples as u32)
+        });
+    if !supported {
+        warn!(
+            "The adapter can't render with {} samples, falling back to no msaa",
+            samples as u32
+        );
+        samples = SampleCount::One;
+    }
+    info!("Using {} msaa samples", samples as u32);
+    samples
+}
+
+fn configure_surface(
+    surface: &Surface,
+    device: &wgpu::Device,
+    size: UVec2,
+    present_mode: wgpu::PresentMode,
+    alpha_mode: wgpu::CompositeAlphaMode,
+    usage: wgpu::TextureUsages,
+) {
+    let config = wgpu::SurfaceConfiguration {
+        usage,
+        format: wgpu::TextureFormat::Bgra8Unorm,
+        width: size.x,
+        height: size.y,
+        present_mode,
+        alpha_mode,
+        view_formats: Vec::new(),
+    };
+    surface.configure(device, &config);
+}
+
 struct StoredSurfaceInfo {
     size: UVec2,
     scale_factor: f32,
     sample_count: SampleCount,
     present_mode: wgpu::PresentMode,
+    alpha_mode: wgpu::CompositeAlphaMode,
+    usage: wgpu::TextureUsages,
 }
 
 #[cfg_attr(
@@ -998,6 +5553,7 @@ pub fn main() {
                     };
                     let window_size = window.inner_size();
                     let iad = app.create_iad().await.unwrap();
+                    app.samples = negotiate_sample_count(app.msaa, &iad.adapter);
                     let mut surface = if cfg!(target_os = "android") {
                         None
                     } else {
@@ -1007,7 +5563,7 @@ pub fn main() {
                     };
                     let renderer = rend3::Renderer::new(
                         iad.clone(),
-                        Handedness::Right,
+                        app.handedness,
                         Some(window_size.width as f32 / window_size.height as f32),
                     )
                     .unwrap();
@@ -1016,29 +5572,28 @@ pub fn main() {
                         let format = TextureFormat::Bgra8Unorm;
                         //                        let format = caps.formats[0];
 
+                        let preferred = if app.low_latency {
+                            &LOW_LATENCY_PRESENT_MODES[..]
+                        } else {
+                            std::slice::from_ref(&app.present_mode)
+                        };
+                        let capabilities = s.get_capabilities(&iad.adapter);
+                        app.supported_present_modes = capabilities.present_modes.clone();
+                        app.present_mode =
+                            negotiate_present_mode(preferred, &capabilities.present_modes);
+                        app.alpha_mode =
+                            negotiate_alpha_mode(app.alpha_mode, &capabilities.alpha_modes);
+                        app.surface_usage = app.negotiate_surface_usage(capabilities.usages);
+
                         // Configure the surface to be ready for rendering.
-                        rend3::configure_surface(
+                        configure_surface(
                             s,
                             &iad.device,
-                            format,
                             glam::UVec2::new(window_size.width, window_size.height),
-                            rend3::types::PresentMode::Immediate,
+                            app.present_mode,
+                            app.alpha_mode,
+                            app.surface_usage,
                         );
-                        let alpha_mode = wgpu::CompositeAlphaMode::Auto;
-                        let config = wgpu::SurfaceConfiguration {
-                            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                                | wgpu::TextureUsages::COPY_DST,
-                            format: wgpu::TextureFormat::Bgra8Unorm,
-                            width: window_size.width,
-                            height: window_size.height,
-                            present_mode: wgpu::PresentMode::Immediate,
-                            alpha_mode,
-                            view_formats: Vec::new(),
-                        };
-                        surface
-                            .as_ref()
-                            .unwrap()
-                            .configure(&renderer.device, &config);
 
                         format
                     });
@@ -1081,6 +5636,8 @@ pub fn main() {
                         scale_factor: app.scale_factor(),
                         sample_count: app.sample_count(),
                         present_mode: app.present_mode(),
+                        alpha_mode: app.alpha_mode,
+                        usage: app.surface_usage,
                     };
                     #[allow(clippy::let_unit_value)]
                     let _ = winit_run(event_loop, move |event, event_loop_window_target| {
@@ -1160,6 +5717,20 @@ fn handle_surface(
     format: rend3::types::TextureFormat,
     surface_info: &mut StoredSurfaceInfo,
 ) -> Option<bool> {
+    // Switching the present mode at runtime reconfigures the surface in place, like a resize.
+    if app.present_mode() != surface_info.present_mode {
+        if let Some(surface) = surface {
+            surface_info.present_mode = app.present_mode();
+            configure_surface(
+                surface,
+                &renderer.device,
+                surface_info.size,
+                surface_info.present_mode,
+                surface_info.alpha_mode,
+                surface_info.usage,
+            );
+        }
+    }
     match *event {
         Event::Resumed => {
             if surface.is_none() {
@@ -1180,6 +5751,7 @@ fn handle_surface(
             log::debug!("resize {:?}", size);
 
             let size = UVec2::new(size.width, size.height);
+            #[cfg(feature = "puppet")]
             if let Some(ref mut inox_renderer) = app.inox_renderer {
                 inox_renderer.resize(size)
             };
@@ -1208,43 +5780,33 @@ fn handle_surface(
                 style.set_property("height", "100%").unwrap();
             }
 
-            let inox_texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("inox texture"),
-                size: Extent3d {
-                    width: size.x,
-                    height: size.y,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Bgra8Unorm,
-                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
-                view_formats: &[wgpu::TextureFormat::Bgra8Unorm],
-            });
-            app.inox_texture = Some(inox_texture);
+            #[cfg(feature = "puppet")]
+            if app.inox_renderer.is_some() {
+                let inox_texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("inox texture"),
+                    size: Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Bgra8Unorm,
+                    usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[wgpu::TextureFormat::Bgra8Unorm],
+                });
+                app.inox_texture = Some(inox_texture);
+            }
             // Reconfigure the surface for the new size.
-            rend3::configure_surface(
+            configure_surface(
                 surface.as_ref().unwrap(),
                 &renderer.device,
-                TextureFormat::Bgra8Unorm,
                 size,
                 surface_info.present_mode,
+                surface_info.alpha_mode,
+                surface_info.usage,
             );
-            let alpha_mode = wgpu::CompositeAlphaMode::Auto;
-            let config = wgpu::SurfaceConfiguration {
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
-                format: wgpu::TextureFormat::Bgra8Unorm,
-                width: size.x,
-                height: size.y,
-                present_mode: wgpu::PresentMode::Immediate,
-                alpha_mode,
-                view_formats: Vec::new(),
-            };
-            surface
-                .as_ref()
-                .unwrap()
-                .configure(&renderer.device, &config);
             // Tell the renderer about the new aspect ratio.
             renderer.set_aspect_ratio(size.x as f32 / size.y as f32);
             Some(false)
@@ -0,0 +1,77 @@
+use glam::Vec3;
+use rend3::types::PointLight;
+
+/// Intensity of lights created from the command line when --point-light-intensity isn't given.
+pub const PUNCTUAL_LIGHT_INTENSITY: f32 = 10.0;
+/// Range of lights created from the command line when none is given.
+pub const PUNCTUAL_LIGHT_RANGE: f32 = 10.0;
+
+/// Cone of a spot light.
+#[derive(Debug, Clone, Copy)]
+pub struct Spot {
+    pub direction: Vec3,
+    /// Half angle of the cone in radians.
+    pub angle: f32,
+}
+
+/// A point or spot light requested on the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct PunctualLight {
+    pub position: Vec3,
+    pub color: Vec3,
+    pub range: f32,
+    pub spot: Option<Spot>,
+}
+
+impl PunctualLight {
+    /// Parses `x,y,z[,r,g,b[,range]]`.
+    pub fn parse_point(values: &[f32]) -> Result<Self, &'static str> {
+        let (position, rest) = match values {
+            [x, y, z, rest @ ..] => (Vec3::new(*x, *y, *z), rest),
+            _ => return Err("Point lights need at least a position"),
+        };
+        let (color, range) = match *rest {
+            [] => (Vec3::ONE, PUNCTUAL_LIGHT_RANGE),
+            [r, g, b] => (Vec3::new(r, g, b), PUNCTUAL_LIGHT_RANGE),
+            [r, g, b, range] => (Vec3::new(r, g, b), range),
+            _ => return Err("Point lights are defined as x,y,z[,r,g,b[,range]]"),
+        };
+
+        Ok(Self {
+            position,
+            color,
+            range,
+            spot: None,
+        })
+    }
+
+    /// Parses `x,y,z,dx,dy,dz,angle[,r,g,b[,range]]` with the angle in degrees.
+    pub fn parse_spot(values: &[f32]) -> Result<Self, &'static str> {
+        let (position, direction, angle, rest) = match values {
+            [x, y, z, dx, dy, dz, angle, rest @ ..] => {
+                ([*x, *y, *z], Vec3::new(*dx, *dy, *dz), *angle, rest)
+            }
+            _ => return Err("Spot lights need at least a position, direction and angle"),
+        };
+        if direction.length_squared() == 0.0 {
+            return Err("Spot light direction cannot be zero");
+        }
+
+        let mut light = Self::parse_point(&[&position[..], rest].concat())
+            .map_err(|_| "Spot lights are defined as x,y,z,dx,dy,dz,angle[,r,g,b[,range]]")?;
+        light.spot = Some(Spot {
+            direction: direction.normalize(),
+            angle: angle.to_radians(),
+        });
+        Ok(light)
+    }
+
+    pub fn to_point_light(self, intensity: f32) -> PointLight {
+        PointLight {
+            position: self.position,
+            color: self.color,
+            radius: self.range,
+            intensity,
+        }
+    }
+}
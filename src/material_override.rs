@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use glam::{Vec3, Vec4};
+use rend3::{types::TextureHandle, Renderer};
+use rend3_gltf::{GltfLoadSettings, ImageKey, LoadedGltfScene};
+use rend3_routine::pbr::{
+    AlbedoComponent, AoMRTextures, MaterialComponent, NormalTexture, PbrMaterial, SampleType,
+    Transparency,
+};
+
+/// Global change to the roughness and metallic factors of every material. Each goes from -1 to
+/// 1, pushing the factors towards 0 when negative and towards 1 when positive. Textures still
+/// scale the factors.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MaterialAdjustment {
+    pub roughness: f32,
+    pub metallic: f32,
+}
+
+impl MaterialAdjustment {
+    pub fn is_none(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn adjust(factor: f32, amount: f32) -> f32 {
+    if amount < 0.0 {
+        factor * (1.0 + amount)
+    } else {
+        factor + (1.0 - factor) * amount
+    }
+}
+
+/// Whether any material sets KHR_materials_emissive_strength, which rend3-gltf doesn't apply.
+pub fn uses_emissive_strength(document: &gltf::Document) -> bool {
+    document
+        .materials()
+        .any(|material| material.emissive_strength().is_some())
+}
+
+/// Rebuilds the scene's materials from the gltf document with `adjustment` applied. rend3 can't
+/// read materials back, so this goes by what the document says, with the textures rend3-gltf
+/// uploaded for it. `color_textures` replaces the base color and emissive textures of these
+/// image indices. Emissive factors are multiplied by their emissive strength and
+/// `emissive_scale`. With no adjustment and no replacements, this restores the materials as they
+/// were loaded, with their emissive strength.
+pub fn apply(
+    renderer: &Renderer,
+    scene: &LoadedGltfScene,
+    document: &gltf::Document,
+    settings: &GltfLoadSettings,
+    adjustment: MaterialAdjustment,
+    color_textures: &HashMap<usize, TextureHandle>,
+    emissive_scale: f32,
+) {
+    profiling::scope!("adjust materials");
+
+    let texture = |texture: gltf::Texture<'_>, srgb: bool| -> Option<TextureHandle> {
+        let index = texture.source().index();
+        if let Some(replacement) = color_textures.get(&index).filter(|_| srgb) {
+            return Some(replacement.clone());
+        }
+        let key = ImageKey { index, srgb };
+        scene.images.get(&key).map(|image| image.inner.clone())
+    };
+
+    for (material, handle) in document.materials().zip(&scene.materials) {
+        let pbr = material.pbr_metallic_roughness();
+        let base_color = pbr
+            .base_color_texture()
+            .and_then(|info| texture(info.texture(), true));
+        let metallic_roughness = pbr.metallic_roughness_texture();
+        let occlusion = material.occlusion_texture();
+        let emissive = material
+            .emissive_texture()
+            .and_then(|info| texture(info.texture(), true));
+        let nearest = pbr.base_color_texture().map_or(false, |info| {
+            info.texture().sampler().mag_filter() == Some(gltf::texture::MagFilter::Nearest)
+        });
+
+        let aomr_textures = match (&metallic_roughness, &occlusion) {
+            (Some(mr), Some(ao))
+                if mr.texture().source().index() == ao.texture().source().index() =>
+            {
+                AoMRTextures::Combined {
+                    texture: texture(mr.texture(), false),
+                }
+            }
+            _ => AoMRTextures::GltfSplit {
+                mr_texture: metallic_roughness
+                    .as_ref()
+                    .and_then(|info| texture(info.texture(), false)),
+                ao_texture: occlusion
+                    .as_ref()
+                    .and_then(|info| texture(info.texture(), false)),
+            },
+        };
+
+        let base_color_factor = Vec4::from(pbr.base_color_factor());
+        let emissive_factor = Vec3::from(material.emissive_factor())
+            * material.emissive_strength().unwrap_or(1.0)
+            * emissive_scale;
+        renderer.update_material(
+            &handle.inner,
+            PbrMaterial {
+                albedo: match base_color {
+                    Some(texture) => AlbedoComponent::TextureVertexValue {
+                        texture,
+                        srgb: false,
+                        value: base_color_factor,
+                    },
+                    None => AlbedoComponent::ValueVertex {
+                        value: base_color_factor,
+                        srgb: false,
+                    },
+                },
+                transparency: match material.alpha_mode() {
+                    gltf::material::AlphaMode::Opaque => Transparency::Opaque,
+                    gltf::material::AlphaMode::Mask => Transparency::Cutout {
+                        cutout: material.alpha_cutoff().unwrap_or(0.5),
+                    },
+                    gltf::material::AlphaMode::Blend => Transparency::Blend,
+                },
+                normal: material
+                    .normal_texture()
+                    .and_then(|info| texture(info.texture(), false))
+                    .map_or(NormalTexture::None, |texture| {
+                        NormalTexture::Tricomponent(texture, settings.normal_direction)
+                    }),
+                aomr_textures,
+                ao_factor: Some(occlusion.map_or(1.0, |info| info.strength())),
+                metallic_factor: Some(adjust(pbr.metallic_factor(), adjustment.metallic)),
+                roughness_factor: Some(adjust(pbr.roughness_factor(), adjustment.roughness)),
+                emissive: match emissive {
+                    Some(texture) => MaterialComponent::TextureValue {
+                        texture,
+                        value: emissive_factor,
+                    },
+                    None => MaterialComponent::Value(emissive_factor),
+                },
+                unlit: material.unlit(),
+                sample_type: if nearest {
+                    SampleType::Nearest
+                } else {
+                    SampleType::Linear
+                },
+                ..PbrMaterial::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use glam::Vec3;
+use rustc_hash::FxHashMap;
+
+/// How many of the primitives with the most problems are logged.
+const WORST_OFFENDERS: usize = 5;
+
+/// Problems found in one triangle primitive of a mesh.
+#[derive(Debug, Default)]
+struct PrimitiveReport {
+    mesh: String,
+    primitive: usize,
+    triangles: usize,
+    /// Triangles using the same vertex more than once.
+    degenerate: usize,
+    /// Triangles with three different vertices but no area, like ones with all corners in a line.
+    zero_area: usize,
+    /// Indices past the end of the vertices, the triangles using them are skipped.
+    invalid_indices: usize,
+    /// Edges shared by more than two triangles, with vertices at the same position counted as
+    /// one so that uv seams don't count.
+    non_manifold_edges: usize,
+    missing_normals: bool,
+    /// No texture coordinates while the material has textures.
+    missing_uvs: bool,
+}
+
+impl PrimitiveReport {
+    fn problems(&self) -> usize {
+        self.degenerate
+            + self.zero_area
+            + self.invalid_indices
+            + self.non_manifold_edges
+            + self.missing_normals as usize
+            + self.missing_uvs as usize
+    }
+}
+
+/// Checks of the geometry of a gltf file for asset QA, from --validate.
+pub struct MeshValidation {
+    reports: Vec<PrimitiveReport>,
+}
+
+impl MeshValidation {
+    /// Scans every triangle primitive of every mesh in `document`, with `buffers` holding the
+    /// data of each gltf buffer. Other primitive modes are skipped.
+    pub fn new(document: &gltf::Document, buffers: &[Vec<u8>]) -> Self {
+        profiling::scope!("validate meshes");
+
+        let mut reports = Vec::new();
+        for mesh in document.meshes() {
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    continue;
+                }
+                let reader =
+                    primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+                let positions: Vec<Vec3> = match reader.read_positions() {
+                    Some(positions) => positions.map(Vec3::from_array).collect(),
+                    None => Vec::new(),
+                };
+                let indices: Vec<u32> = match reader.read_indices() {
+                    Some(indices) => indices.into_u32().collect(),
+                    None => (0..positions.len() as u32).collect(),
+                };
+                let material = primitive.material();
+                let pbr = material.pbr_metallic_roughness();
+                let textured = pbr.base_color_texture().is_some()
+                    || pbr.metallic_roughness_texture().is_some()
+                    || material.normal_texture().is_some()
+                    || material.occlusion_texture().is_some()
+                    || material.emissive_texture().is_some();
+
+                let mut report = PrimitiveReport {
+                    mesh: mesh
+                        .name()
+                        .map_or_else(|| mesh.index().to_string(), str::to_owned),
+                    primitive: primitive.index(),
+                    triangles: indices.len() / 3,
+                    missing_normals: reader.read_normals().is_none(),
+                    missing_uvs: textured && reader.read_tex_coords(0).is_none(),
+                    ..Default::default()
+                };
+                check_triangles(&mut report, &positions, &indices);
+                reports.push(report);
+            }
+        }
+        Self { reports }
+    }
+
+    /// Logs the totals, and the primitives with the most problems.
+    pub fn log(&self) {
+        let total = |count: fn(&PrimitiveReport) -> usize| -> usize {
+            self.reports.iter().map(count).sum()
+        };
+        let problems = total(PrimitiveReport::problems);
+        let summary = format!(
+            "Validated {} primitives with {} triangles: {} degenerate triangles, {} zero area \
+             triangles, {} invalid indices, {} non-manifold edges, {} primitives without \
+             normals, {} textured primitives without uvs",
+            self.reports.len(),
+            total(|report| report.triangles),
+            total(|report| report.degenerate),
+            total(|report| report.zero_area),
+            total(|report| report.invalid_indices),
+            total(|report| report.non_manifold_edges),
+            total(|report| report.missing_normals as usize),
+            total(|report| report.missing_uvs as usize),
+        );
+        if problems == 0 {
+            log::info!("{}", summary);
+            return;
+        }
+        log::warn!("{}", summary);
+
+        let mut worst: Vec<&PrimitiveReport> = self
+            .reports
+            .iter()
+            .filter(|report| report.problems() > 0)
+            .collect();
+        worst.sort_by_key(|report| std::cmp::Reverse(report.problems()));
+        for report in worst.into_iter().take(WORST_OFFENDERS) {
+            log::warn!(
+                "Mesh {} primitive {}: {} of {} triangles degenerate, {} zero area, \
+                 {} invalid indices, {} non-manifold edges{}{}",
+                report.mesh,
+                report.primitive,
+                report.degenerate,
+                report.triangles,
+                report.zero_area,
+                report.invalid_indices,
+                report.non_manifold_edges,
+                if report.missing_normals {
+                    ", no normals"
+                } else {
+                    ""
+                },
+                if report.missing_uvs {
+                    ", textured without uvs"
+                } else {
+                    ""
+                },
+            );
+        }
+    }
+}
+
+fn check_triangles(report: &mut PrimitiveReport, positions: &[Vec3], indices: &[u32]) {
+    let (min, max) = positions.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), &p| (min.min(p), max.max(p)),
+    );
+    // Relative to the size of the primitive, so the scale it is modelled at doesn't matter.
+    let min_area = (max - min).length_squared() * 1e-12;
+
+    // Welds vertices at the same position, so edges are matched across uv and normal seams.
+    let mut welded = FxHashMap::default();
+    let mut weld = |index: u32| -> u32 {
+        let bits = positions[index as usize].to_array().map(f32::to_bits);
+        let next = welded.len() as u32;
+        *welded.entry(bits).or_insert(next)
+    };
+    let mut edges = FxHashMap::<(u32, u32), u32>::default();
+
+    for triangle in indices.chunks_exact(3) {
+        let invalid = triangle
+            .iter()
+            .filter(|&&index| index as usize >= positions.len())
+            .count();
+        if invalid > 0 {
+            report.invalid_indices += invalid;
+            continue;
+        }
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+        if a == b || b == c || a == c {
+            report.degenerate += 1;
+            continue;
+        }
+        let [pa, pb, pc] = [a, b, c].map(|index| positions[index as usize]);
+        if (pb - pa).cross(pc - pa).length() * 0.5 <= min_area {
+            report.zero_area += 1;
+            continue;
+        }
+        let [wa, wb, wc] = [a, b, c].map(&mut weld);
+        for (start, end) in [(wa, wb), (wb, wc), (wc, wa)] {
+            *edges.entry((start.min(end), start.max(end))).or_default() += 1;
+        }
+    }
+    report.non_manifold_edges = edges.values().filter(|&&count| count > 2).count();
+}
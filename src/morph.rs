@@ -0,0 +1,162 @@
+use glam::Vec3;
+use rustc_hash::FxHashSet;
+
+use crate::scene_info;
+
+/// A morph target weight from --morph-weight.
+#[derive(Debug, Clone)]
+pub struct MorphWeight {
+    /// Only set the target on the mesh with this name, instead of on every mesh that has it.
+    pub mesh: Option<String>,
+    pub target: String,
+    pub weight: f32,
+}
+
+/// Adds the displacements of the morph targets, at the weights `weights` set, into the positions
+/// and normals of their meshes, as rend3 only renders the base shape. The other targets of those
+/// meshes keep their default weight, meshes none of `weights` apply to are left alone.
+///
+/// `buffers` are the document's buffers, in order. Returns how many meshes were changed.
+pub fn bake(document: &gltf::Document, buffers: &mut [Vec<u8>], weights: &[MorphWeight]) -> usize {
+    let mut used = vec![false; weights.len()];
+    // Primitives can share their vertices, which must only be moved once.
+    let mut baked_accessors = FxHashSet::default();
+    let mut baked_meshes = 0;
+    for targets in scene_info::morph_targets(document) {
+        let mut mesh_weights = targets.default_weights.clone();
+        let mut changed = false;
+        for (weight, used) in weights.iter().zip(&mut used) {
+            if weight
+                .mesh
+                .as_ref()
+                .is_some_and(|mesh| *mesh != targets.mesh)
+            {
+                continue;
+            }
+            if let Some(idx) = targets
+                .targets
+                .iter()
+                .position(|name| *name == weight.target)
+            {
+                mesh_weights[idx] = weight.weight;
+                *used = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            continue;
+        }
+        let Some(mesh) = document.meshes().nth(targets.index) else {
+            continue;
+        };
+        for primitive in mesh.primitives() {
+            bake_primitive(
+                &targets.mesh,
+                &primitive,
+                buffers,
+                &mesh_weights,
+                &mut baked_accessors,
+            );
+        }
+        baked_meshes += 1;
+    }
+    for (weight, _) in weights.iter().zip(used).filter(|(_, used)| !used) {
+        match weight.mesh {
+            Some(ref mesh) => log::warn!("Mesh {} has no morph target {}", mesh, weight.target),
+            None => log::warn!("No mesh has a morph target {}", weight.target),
+        }
+    }
+    baked_meshes
+}
+
+fn bake_primitive(
+    mesh: &str,
+    primitive: &gltf::Primitive,
+    buffers: &mut [Vec<u8>],
+    weights: &[f32],
+    baked_accessors: &mut FxHashSet<usize>,
+) {
+    let Some(positions) = primitive.get(&gltf::Semantic::Positions) else {
+        return;
+    };
+    let normals = primitive.get(&gltf::Semantic::Normals);
+    let mut position_deltas = vec![Vec3::ZERO; positions.count()];
+    let mut normal_deltas = vec![Vec3::ZERO; normals.as_ref().map_or(0, |normals| normals.count())];
+    {
+        let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+        for ((target_positions, target_normals, _), &weight) in
+            reader.read_morph_targets().zip(weights)
+        {
+            if weight == 0.0 {
+                continue;
+            }
+            for (delta, displacement) in position_deltas
+                .iter_mut()
+                .zip(target_positions.into_iter().flatten())
+            {
+                *delta += Vec3::from_array(displacement) * weight;
+            }
+            for (delta, displacement) in normal_deltas
+                .iter_mut()
+                .zip(target_normals.into_iter().flatten())
+            {
+                *delta += Vec3::from_array(displacement) * weight;
+            }
+        }
+    }
+    if baked_accessors.insert(positions.index())
+        && !add_to_accessor(&positions, buffers, &position_deltas, false)
+    {
+        log::warn!(
+            "Can't apply morph weights to the positions of mesh {}, they aren't plain floats",
+            mesh
+        );
+    }
+    if let Some(normals) = normals {
+        if baked_accessors.insert(normals.index()) {
+            add_to_accessor(&normals, buffers, &normal_deltas, true);
+        }
+    }
+}
+
+/// Adds `deltas` to the vectors of a float vec3 accessor in place, normalizing the results if
+/// `normalize` is set. Returns false for sparse or other accessors, which are left alone.
+fn add_to_accessor(
+    accessor: &gltf::Accessor,
+    buffers: &mut [Vec<u8>],
+    deltas: &[Vec3],
+    normalize: bool,
+) -> bool {
+    if accessor.sparse().is_some()
+        || accessor.data_type() != gltf::accessor::DataType::F32
+        || accessor.dimensions() != gltf::accessor::Dimensions::Vec3
+    {
+        return false;
+    }
+    let Some(view) = accessor.view() else {
+        return false;
+    };
+    let stride = view.stride().unwrap_or(12);
+    let start = view.offset() + accessor.offset();
+    let Some(buffer) = buffers.get_mut(view.buffer().index()) else {
+        return false;
+    };
+    for (idx, delta) in deltas.iter().enumerate().take(accessor.count()) {
+        let offset = start + idx * stride;
+        let Some(bytes) = buffer.get_mut(offset..offset + 12) else {
+            return false;
+        };
+        let mut components = [0.0; 3];
+        for (component, chunk) in components.iter_mut().zip(bytes.chunks_exact(4)) {
+            *component = f32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let mut value = Vec3::from_array(components) + *delta;
+        if normalize {
+            value = value.normalize_or_zero();
+        }
+        for (chunk, component) in bytes.chunks_exact_mut(4).zip(value.to_array()) {
+            chunk.copy_from_slice(&component.to_le_bytes());
+        }
+    }
+    true
+}
@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+
+use glam::{UVec2, Vec2, Vec4};
+use wgpu::util::DeviceExt;
+
+use crate::font;
+
+const SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
+";
+
+/// Position (2) + color (4).
+const FLOATS_PER_VERTEX: usize = 6;
+
+/// Immediate mode screen space drawing on top of the final frame.
+///
+/// Shapes are queued in pixel coordinates (origin top left) during the frame
+/// and flushed into the target with a single render pass in [`Overlay::render`].
+pub struct Overlay {
+    pipeline: wgpu::RenderPipeline,
+    vertices: Vec<f32>,
+}
+
+impl Overlay {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("overlay shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("overlay pipeline layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("overlay pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: (FLOATS_PER_VERTEX * std::mem::size_of::<f32>()) as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            vertices: Vec::new(),
+        }
+    }
+
+    fn vertex(&mut self, position: Vec2, color: Vec4) {
+        self.vertices.extend_from_slice(&[position.x, position.y]);
+        self.vertices.extend_from_slice(&color.to_array());
+    }
+
+    /// Queues an axis aligned rectangle spanning `min..max` in pixels.
+    pub fn rect(&mut self, min: Vec2, max: Vec2, color: Vec4) {
+        let top_right = Vec2::new(max.x, min.y);
+        let bottom_left = Vec2::new(min.x, max.y);
+
+        self.vertex(min, color);
+        self.vertex(bottom_left, color);
+        self.vertex(top_right, color);
+        self.vertex(top_right, color);
+        self.vertex(bottom_left, color);
+        self.vertex(max, color);
+    }
+
+    /// Size in pixels of `text` drawn with [`Overlay::text`] at the given scale.
+    pub fn text_size(text: &str, scale: f32) -> Vec2 {
+        let columns = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as u32;
+        let rows = text.lines().count().max(1) as u32;
+        let width = (columns * font::GLYPH_ADVANCE).saturating_sub(1);
+        let height = (rows - 1) * font::LINE_ADVANCE + font::GLYPH_HEIGHT;
+        Vec2::new(width as f32, height as f32) * scale
+    }
+
+    /// Queues `text` with its top left corner at `position`. Every font pixel becomes a
+    /// `scale` sized square, and `\n` starts a new line.
+    pub fn text(&mut self, position: Vec2, scale: f32, color: Vec4, text: &str) {
+        for (row, line) in text.lines().enumerate() {
+            let line_y = position.y + (row as u32 * font::LINE_ADVANCE) as f32 * scale;
+            for (column, c) in line.chars().enumerate() {
+                let glyph_x = position.x + (column as u32 * font::GLYPH_ADVANCE) as f32 * scale;
+                for (y, bits) in font::glyph(c).into_iter().enumerate() {
+                    for x in 0..font::GLYPH_WIDTH {
+                        if bits & (1 << (font::GLYPH_WIDTH - 1 - x)) == 0 {
+                            continue;
+                        }
+                        let min = Vec2::new(glyph_x + x as f32 * scale, line_y + y as f32 * scale);
+                        self.rect(min, min + Vec2::splat(scale), color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws everything queued since the last call over the contents of `target`.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        resolution: UVec2,
+    ) {
+        profiling::scope!("overlay");
+
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        let resolution = resolution.as_vec2();
+        let contents: Vec<u8> = self
+            .vertices
+            .chunks_exact(FLOATS_PER_VERTEX)
+            .flat_map(|v| {
+                // Pixels to normalized device coordinates.
+                let x = v[0] / resolution.x * 2.0 - 1.0;
+                let y = 1.0 - v[1] / resolution.y * 2.0;
+                [x, y, v[2], v[3], v[4], v[5]]
+            })
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+        let vertex_count = (self.vertices.len() / FLOATS_PER_VERTEX) as u32;
+        self.vertices.clear();
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay vertices"),
+            contents: &contents,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("overlay encoder"),
+        });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("overlay pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(&self.pipeline);
+            rpass.set_vertex_buffer(0, buffer.slice(..));
+            rpass.draw(0..vertex_count, 0..1);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
@@ -0,0 +1,47 @@
+use glam::{Mat4, Vec2, Vec3};
+
+/// The ray from the camera through `cursor`, in pixels from the top left of a viewport of
+/// `size` pixels, given the inverse of the viewport's view projection matrix. Returns the origin
+/// on the near plane and the normalized direction.
+pub fn cursor_ray(cursor: Vec2, size: Vec2, inverse_view_proj: Mat4) -> (Vec3, Vec3) {
+    let ndc = Vec2::new(cursor.x / size.x * 2.0 - 1.0, 1.0 - cursor.y / size.y * 2.0);
+    // rend3 uses reverse z, the near plane is at 1 and infinity at 0.
+    let near = inverse_view_proj.project_point3(ndc.extend(1.0));
+    let far = inverse_view_proj.project_point3(ndc.extend(0.5));
+    (near, (far - near).normalize())
+}
+
+/// Distance along the ray to the nearest triangle it hits, from either side.
+pub fn ray_cast(origin: Vec3, direction: Vec3, positions: &[Vec3], indices: &[u32]) -> Option<f32> {
+    indices
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|idx| positions[triangle[idx] as usize]);
+            ray_triangle(origin, direction, a, b, c)
+        })
+        .min_by(f32::total_cmp)
+}
+
+/// Möller-Trumbore intersection.
+fn ray_triangle(origin: Vec3, direction: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    let inverse = 1.0 / determinant;
+    let to_origin = origin - a;
+    let u = to_origin.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(edge1);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inverse;
+    (t > 0.0).then_some(t)
+}
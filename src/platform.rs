@@ -17,6 +17,39 @@ cfg_if::cfg_if!(
             pub const SHIFT: u32 = 0x38;
             pub const ESCAPE: u32 = 0x35;
             pub const LALT: u32 = 0x3A; // Actually Left Option
+            pub const T: u32 = 0x11;
+            pub const Y: u32 = 0x10;
+            pub const B: u32 = 0x0B;
+            pub const G: u32 = 0x05;
+            pub const C: u32 = 0x08;
+            pub const V: u32 = 0x09;
+            pub const TAB: u32 = 0x30;
+            pub const SPACE: u32 = 0x31;
+            pub const K: u32 = 0x28;
+            pub const F: u32 = 0x03;
+            pub const MINUS: u32 = 0x1B;
+            pub const EQUAL: u32 = 0x18;
+            pub const I: u32 = 0x22;
+            pub const L: u32 = 0x25;
+            pub const H: u32 = 0x04;
+            pub const F1: u32 = 0x7A;
+            pub const F2: u32 = 0x78;
+            pub const F3: u32 = 0x63;
+            pub const F4: u32 = 0x76;
+            pub const F5: u32 = 0x60;
+            pub const F6: u32 = 0x61;
+            pub const F7: u32 = 0x62;
+            pub const F9: u32 = 0x65;
+            pub const F12: u32 = 0x6F;
+            pub const O: u32 = 0x1F;
+            pub const N: u32 = 0x2D;
+            pub const DIGIT1: u32 = 0x12;
+            pub const DIGIT2: u32 = 0x13;
+            pub const DIGIT3: u32 = 0x14;
+            pub const DIGIT4: u32 = 0x15;
+            pub const DIGIT0: u32 = 0x1D;
+            pub const BRACKET_LEFT: u32 = 0x21;
+            pub const BRACKET_RIGHT: u32 = 0x1E;
         }
     } else if #[cfg(target_arch = "wasm32")] {
         pub mod Scancodes {
@@ -35,6 +68,39 @@ cfg_if::cfg_if!(
             pub const SHIFT: u32 = KeyCode::ShiftLeft as u32;
             pub const ESCAPE: u32 = KeyCode::Escape as u32;
             pub const LALT: u32 = KeyCode::AltLeft as u32;
+            pub const T: u32 = KeyCode::KeyT as u32;
+            pub const Y: u32 = KeyCode::KeyY as u32;
+            pub const B: u32 = KeyCode::KeyB as u32;
+            pub const G: u32 = KeyCode::KeyG as u32;
+            pub const C: u32 = KeyCode::KeyC as u32;
+            pub const V: u32 = KeyCode::KeyV as u32;
+            pub const TAB: u32 = KeyCode::Tab as u32;
+            pub const SPACE: u32 = KeyCode::Space as u32;
+            pub const K: u32 = KeyCode::KeyK as u32;
+            pub const F: u32 = KeyCode::KeyF as u32;
+            pub const MINUS: u32 = KeyCode::Minus as u32;
+            pub const EQUAL: u32 = KeyCode::Equal as u32;
+            pub const I: u32 = KeyCode::KeyI as u32;
+            pub const L: u32 = KeyCode::KeyL as u32;
+            pub const H: u32 = KeyCode::KeyH as u32;
+            pub const F1: u32 = KeyCode::F1 as u32;
+            pub const F2: u32 = KeyCode::F2 as u32;
+            pub const F3: u32 = KeyCode::F3 as u32;
+            pub const F4: u32 = KeyCode::F4 as u32;
+            pub const F5: u32 = KeyCode::F5 as u32;
+            pub const F6: u32 = KeyCode::F6 as u32;
+            pub const F7: u32 = KeyCode::F7 as u32;
+            pub const F9: u32 = KeyCode::F9 as u32;
+            pub const F12: u32 = KeyCode::F12 as u32;
+            pub const O: u32 = KeyCode::KeyO as u32;
+            pub const N: u32 = KeyCode::KeyN as u32;
+            pub const DIGIT1: u32 = KeyCode::Digit1 as u32;
+            pub const DIGIT2: u32 = KeyCode::Digit2 as u32;
+            pub const DIGIT3: u32 = KeyCode::Digit3 as u32;
+            pub const DIGIT4: u32 = KeyCode::Digit4 as u32;
+            pub const DIGIT0: u32 = KeyCode::Digit0 as u32;
+            pub const BRACKET_LEFT: u32 = KeyCode::BracketLeft as u32;
+            pub const BRACKET_RIGHT: u32 = KeyCode::BracketRight as u32;
         }
     } else {
         pub mod Scancodes {
@@ -52,6 +118,39 @@ cfg_if::cfg_if!(
             pub const SHIFT: u32 = 0x2A;
             pub const ESCAPE: u32 = 0x01;
             pub const LALT: u32 = 0x38;
+            pub const T: u32 = 0x14;
+            pub const Y: u32 = 0x15;
+            pub const B: u32 = 0x30;
+            pub const G: u32 = 0x22;
+            pub const C: u32 = 0x2E;
+            pub const V: u32 = 0x2F;
+            pub const TAB: u32 = 0x0F;
+            pub const SPACE: u32 = 0x39;
+            pub const K: u32 = 0x25;
+            pub const F: u32 = 0x21;
+            pub const MINUS: u32 = 0x0C;
+            pub const EQUAL: u32 = 0x0D;
+            pub const I: u32 = 0x17;
+            pub const L: u32 = 0x26;
+            pub const H: u32 = 0x23;
+            pub const F1: u32 = 0x3B;
+            pub const F2: u32 = 0x3C;
+            pub const F3: u32 = 0x3D;
+            pub const F4: u32 = 0x3E;
+            pub const F5: u32 = 0x3F;
+            pub const F6: u32 = 0x40;
+            pub const F7: u32 = 0x41;
+            pub const F9: u32 = 0x43;
+            pub const F12: u32 = 0x58;
+            pub const O: u32 = 0x18;
+            pub const N: u32 = 0x31;
+            pub const DIGIT1: u32 = 0x02;
+            pub const DIGIT2: u32 = 0x03;
+            pub const DIGIT3: u32 = 0x04;
+            pub const DIGIT4: u32 = 0x05;
+            pub const DIGIT0: u32 = 0x0B;
+            pub const BRACKET_LEFT: u32 = 0x1A;
+            pub const BRACKET_RIGHT: u32 = 0x1B;
         }
     }
 );
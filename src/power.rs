@@ -0,0 +1,21 @@
+/// Whether the machine is running on battery, `None` where that can't be told.
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> Option<bool> {
+    let mut has_battery = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        let read = |name| std::fs::read_to_string(path.join(name)).unwrap_or_default();
+        match read("type").trim() {
+            "Mains" | "USB" if read("online").trim() == "1" => return Some(false),
+            "Battery" => has_battery |= read("present").trim() != "0",
+            _ => {}
+        }
+    }
+    // Desktops without a battery are always on mains, even without a mains supply listed.
+    Some(has_battery)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn on_battery() -> Option<bool> {
+    None
+}
@@ -0,0 +1,174 @@
+use std::borrow::Cow;
+
+use glam::{Mat4, Vec3};
+
+/// Parses the document of a gltf or glb file without touching its buffers.
+///
+/// rend3-gltf only keeps what it needs for rendering, everything else the viewer
+/// wants to know about the file comes from here.
+pub fn parse_document(data: &[u8]) -> anyhow::Result<gltf::Document> {
+    let json = if data.starts_with(b"glTF") {
+        gltf::Glb::from_slice(data)?.json
+    } else {
+        Cow::Borrowed(data)
+    };
+    let root = gltf::json::Root::from_slice(&json)?;
+    Ok(gltf::Document::from_json(root)?)
+}
+
+/// Calls `f` with every node of the default scene and its world transform.
+///
+/// `scale` is the scale the scene was loaded with.
+pub fn visit_nodes(document: &gltf::Document, scale: f32, mut f: impl FnMut(&gltf::Node, Mat4)) {
+    fn visit(node: gltf::Node, parent: Mat4, f: &mut impl FnMut(&gltf::Node, Mat4)) {
+        let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix());
+        f(&node, transform);
+        for child in node.children() {
+            visit(child, transform, f);
+        }
+    }
+
+    let Some(scene) = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    else {
+        return;
+    };
+    for node in scene.nodes() {
+        visit(node, Mat4::from_scale(Vec3::splat(scale)), &mut f);
+    }
+}
+
+/// A camera placed in the gltf scene.
+#[derive(Debug, Clone)]
+pub struct SceneCamera {
+    pub name: Option<String>,
+    pub position: Vec3,
+    /// Normalized direction the camera looks in.
+    pub forward: Vec3,
+    /// Vertical field of view in degrees, `None` for orthographic cameras.
+    pub vfov: Option<f32>,
+}
+
+pub fn cameras(document: &gltf::Document, scale: f32) -> Vec<SceneCamera> {
+    let mut cameras = Vec::new();
+    visit_nodes(document, scale, |node, transform| {
+        let Some(camera) = node.camera() else {
+            return;
+        };
+        let vfov = match camera.projection() {
+            gltf::camera::Projection::Perspective(perspective) => {
+                Some(perspective.yfov().to_degrees())
+            }
+            gltf::camera::Projection::Orthographic(_) => None,
+        };
+        // gltf cameras look down their local -Z
+        cameras.push(SceneCamera {
+            name: camera.name().or(node.name()).map(str::to_owned),
+            position: transform.transform_point3(Vec3::ZERO),
+            forward: transform.transform_vector3(-Vec3::Z).normalize(),
+            vfov,
+        });
+    });
+    cameras
+}
+
+/// The morph targets (blendshapes) of a mesh.
+#[derive(Debug, Clone)]
+pub struct MeshMorphTargets {
+    /// Index of the mesh in the document.
+    pub index: usize,
+    pub mesh: String,
+    /// Names from the mesh's `targetNames` extra where the exporter wrote them, the index otherwise.
+    pub targets: Vec<String>,
+    /// Weights the mesh defaults to, zero where the file doesn't set them.
+    pub default_weights: Vec<f32>,
+}
+
+pub fn morph_targets(document: &gltf::Document) -> Vec<MeshMorphTargets> {
+    document
+        .meshes()
+        .filter_map(|mesh| {
+            let count = mesh
+                .primitives()
+                .map(|primitive| primitive.morph_targets().len())
+                .max()
+                .unwrap_or(0);
+            if count == 0 {
+                return None;
+            }
+            let names: Vec<String> = mesh
+                .extras()
+                .as_ref()
+                .and_then(|extras| {
+                    gltf::json::deserialize::from_str::<gltf::json::Value>(extras.get()).ok()
+                })
+                .and_then(|extras| {
+                    extras["targetNames"].as_array().map(|names| {
+                        names
+                            .iter()
+                            .map(|name| name.as_str().unwrap_or_default().to_owned())
+                            .collect()
+                    })
+                })
+                .unwrap_or_default();
+            let weights = mesh.weights().unwrap_or_default();
+            Some(MeshMorphTargets {
+                index: mesh.index(),
+                mesh: mesh
+                    .name()
+                    .map_or_else(|| format!("#{}", mesh.index()), str::to_owned),
+                targets: (0..count)
+                    .map(|idx| match names.get(idx) {
+                        Some(name) if !name.is_empty() => name.clone(),
+                        _ => idx.to_string(),
+                    })
+                    .collect(),
+                default_weights: (0..count)
+                    .map(|idx| weights.get(idx).copied().unwrap_or(0.0))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Primitives in a mode rend3 doesn't render, which are missing from the scene.
+#[derive(Debug, Clone)]
+pub struct UnsupportedPrimitives {
+    pub mode: gltf::mesh::Mode,
+    pub count: usize,
+    /// Meshes with primitives in this mode.
+    pub meshes: Vec<String>,
+}
+
+/// Every primitive mode other than triangle lists, which is all rend3 draws.
+pub fn unsupported_primitives(document: &gltf::Document) -> Vec<UnsupportedPrimitives> {
+    let mut unsupported: Vec<UnsupportedPrimitives> = Vec::new();
+    for mesh in document.meshes() {
+        let name = mesh
+            .name()
+            .map_or_else(|| format!("#{}", mesh.index()), str::to_owned);
+        for primitive in mesh.primitives() {
+            let mode = primitive.mode();
+            if mode == gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let entry = match unsupported.iter_mut().position(|entry| entry.mode == mode) {
+                Some(idx) => &mut unsupported[idx],
+                None => {
+                    unsupported.push(UnsupportedPrimitives {
+                        mode,
+                        count: 0,
+                        meshes: Vec::new(),
+                    });
+                    unsupported.last_mut().unwrap()
+                }
+            };
+            entry.count += 1;
+            if !entry.meshes.contains(&name) {
+                entry.meshes.push(name.clone());
+            }
+        }
+    }
+    unsupported
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Cursor,
+    time::Duration,
+};
+
+use glam::Vec3;
+use gltf::json::Value;
+
+/// Numbers about a loaded gltf file, logged with --stats and written out with --stats-json for
+/// asset pipelines.
+#[derive(Debug, Clone)]
+pub struct SceneStats {
+    pub file: String,
+    pub nodes: usize,
+    pub meshes: usize,
+    pub primitives: usize,
+    /// Vertices and triangles of the meshes in the file, each mesh counted once.
+    pub vertices: usize,
+    pub triangles: usize,
+    /// Triangles of the default scene in its rest pose, with every mesh instance counted.
+    pub scene_triangles: usize,
+    pub materials: usize,
+    pub textures: usize,
+    pub images: usize,
+    pub animations: usize,
+    pub skins: usize,
+    pub cameras: usize,
+    /// Vertex attributes as stored in the file and indices as the 32 bit ones rend3 uses.
+    pub geometry_bytes: u64,
+    /// Images as uncompressed RGBA8 with a full mip chain. Images in a format `image` can't
+    /// read the size of, like ktx2 and dds, aren't included.
+    pub texture_bytes: u64,
+    pub unsized_images: usize,
+    /// Width and height of every image but the unsized ones.
+    pub image_sizes: Vec<(u32, u32)>,
+    /// World space bounds of the default scene in its rest pose.
+    pub bounds: Option<(Vec3, Vec3)>,
+    /// Time reading the file, then loading its resources and uploading them.
+    pub read_time: Duration,
+    pub load_time: Duration,
+}
+
+/// Smallest and largest coordinates of `positions`, `None` without any.
+pub fn bounds(positions: &[Vec3]) -> Option<(Vec3, Vec3)> {
+    positions
+        .iter()
+        .fold(None, |bounds: Option<(Vec3, Vec3)>, &position| {
+            Some(bounds.map_or((position, position), |(min, max)| {
+                (min.min(position), max.max(position))
+            }))
+        })
+}
+
+/// Width and height of an encoded image, without decoding it.
+pub fn image_size(data: &[u8]) -> Option<(u32, u32)> {
+    image::io::Reader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+impl SceneStats {
+    /// `image_sizes` holds the sizes of the images loaded from uris, by uri. `positions` and
+    /// `indices` are the triangles of [`crate::wireframe::collect_triangles`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        file: &str,
+        document: &gltf::Document,
+        buffers: &[Vec<u8>],
+        image_sizes: &HashMap<String, (u32, u32)>,
+        positions: &[Vec3],
+        indices: &[u32],
+        read_time: Duration,
+        load_time: Duration,
+    ) -> Self {
+        let (mut primitives, mut vertices, mut triangles, mut geometry_bytes) = (0, 0, 0, 0);
+        for primitive in document.meshes().flat_map(|mesh| mesh.primitives()) {
+            primitives += 1;
+            let vertex_count = primitive
+                .get(&gltf::Semantic::Positions)
+                .map_or(0, |accessor| accessor.count());
+            let index_count = primitive
+                .indices()
+                .map_or(vertex_count, |accessor| accessor.count());
+            vertices += vertex_count;
+            triangles += match primitive.mode() {
+                gltf::mesh::Mode::Triangles => index_count / 3,
+                gltf::mesh::Mode::TriangleStrip | gltf::mesh::Mode::TriangleFan => {
+                    index_count.saturating_sub(2)
+                }
+                _ => 0,
+            };
+            geometry_bytes += primitive
+                .attributes()
+                .map(|(_, accessor)| (accessor.count() * accessor.size()) as u64)
+                .sum::<u64>();
+            geometry_bytes += index_count as u64 * 4;
+        }
+
+        let (mut texture_bytes, mut unsized_images, mut sizes) = (0, 0, Vec::new());
+        for image in document.images() {
+            let size = match image.source() {
+                gltf::image::Source::View { view, .. } => buffers
+                    .get(view.buffer().index())
+                    .and_then(|buffer| buffer.get(view.offset()..view.offset() + view.length()))
+                    .and_then(image_size),
+                gltf::image::Source::Uri { uri, .. } => image_sizes.get(uri).copied(),
+            };
+            match size {
+                // A full mip chain adds a third.
+                Some((width, height)) => {
+                    texture_bytes += width as u64 * height as u64 * 4 * 4 / 3;
+                    sizes.push((width, height));
+                }
+                None => unsized_images += 1,
+            }
+        }
+
+        let bounds = bounds(positions);
+
+        Self {
+            file: file.to_owned(),
+            nodes: document.nodes().len(),
+            meshes: document.meshes().len(),
+            primitives,
+            vertices,
+            triangles,
+            scene_triangles: indices.len() / 3,
+            materials: document.materials().len(),
+            textures: document.textures().len(),
+            images: document.images().len(),
+            animations: document.animations().len(),
+            skins: document.skins().len(),
+            cameras: document.cameras().len(),
+            geometry_bytes,
+            texture_bytes,
+            unsized_images,
+            image_sizes: sizes,
+            bounds,
+            read_time,
+            load_time,
+        }
+    }
+
+    /// Logs a summary for --stats.
+    pub fn log(&self) {
+        log::info!(
+            "{}: {} meshes with {} primitives, {} vertices and {} triangles, {} in the scene. \
+             {} materials, {} textures",
+            self.file,
+            self.meshes,
+            self.primitives,
+            self.vertices,
+            self.triangles,
+            self.scene_triangles,
+            self.materials,
+            self.textures,
+        );
+        let mut sizes = BTreeMap::<(u32, u32), usize>::new();
+        for &size in &self.image_sizes {
+            *sizes.entry(size).or_default() += 1;
+        }
+        let mut sizes: Vec<String> = sizes
+            .iter()
+            .rev()
+            .map(|(&(width, height), count)| format!("{} x {}x{}", count, width, height))
+            .collect();
+        if self.unsized_images > 0 {
+            sizes.push(format!("{} of unknown size", self.unsized_images));
+        }
+        log::info!(
+            "{} images: {}. About {:.1} MiB of geometry and {:.1} MiB of textures on the gpu",
+            self.images,
+            if sizes.is_empty() {
+                "none".to_owned()
+            } else {
+                sizes.join(", ")
+            },
+            self.geometry_bytes as f64 / (1 << 20) as f64,
+            self.texture_bytes as f64 / (1 << 20) as f64,
+        );
+        match self.bounds {
+            Some((min, max)) => log::info!("Bounds {} to {}, size {}", min, max, max - min),
+            None => log::info!("The scene has no geometry"),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let vector = |v: Vec3| Value::from(v.to_array().to_vec());
+        let bounds = match self.bounds {
+            Some((min, max)) => [
+                ("min", vector(min)),
+                ("max", vector(max)),
+                ("size", vector(max - min)),
+            ]
+            .into_iter()
+            .collect(),
+            None => Value::Null,
+        };
+        let json: Value = [
+            ("file", Value::from(self.file.as_str())),
+            ("nodes", self.nodes.into()),
+            ("meshes", self.meshes.into()),
+            ("primitives", self.primitives.into()),
+            ("vertices", self.vertices.into()),
+            ("triangles", self.triangles.into()),
+            ("scene_triangles", self.scene_triangles.into()),
+            ("materials", self.materials.into()),
+            ("textures", self.textures.into()),
+            ("images", self.images.into()),
+            ("animations", self.animations.into()),
+            ("skins", self.skins.into()),
+            ("cameras", self.cameras.into()),
+            (
+                "estimated_vram_bytes",
+                [
+                    ("geometry", self.geometry_bytes),
+                    ("textures", self.texture_bytes),
+                    ("total", self.geometry_bytes + self.texture_bytes),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ("unsized_images", self.unsized_images.into()),
+            ("bounds", bounds),
+            (
+                "load_seconds",
+                [
+                    ("read", self.read_time.as_secs_f64()),
+                    ("resources", self.load_time.as_secs_f64()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        gltf::json::serialize::to_string_pretty(&json).expect("a json value always serializes")
+    }
+}
@@ -0,0 +1,159 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec3;
+use rend3::types::{Handedness, Mesh, MeshBuilder};
+
+/// Builds simple meshes out of convex parts.
+///
+/// Every part is wound so its faces point away from the part's center, which
+/// keeps the generators free of per-shape winding bookkeeping.
+#[derive(Default)]
+pub struct ShapeBuilder {
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+}
+
+impl ShapeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn convex_part(&mut self, points: Vec<Vec3>, triangles: Vec<[u32; 3]>) {
+        let center = points.iter().copied().sum::<Vec3>() / points.len() as f32;
+        let base = self.positions.len() as u32;
+
+        for [a, b, c] in triangles {
+            let (pa, pb, pc) = (points[a as usize], points[b as usize], points[c as usize]);
+            let normal = (pb - pa).cross(pc - pa);
+            let outwards = (pa + pb + pc) / 3.0 - center;
+            if normal.dot(outwards) >= 0.0 {
+                self.indices
+                    .extend_from_slice(&[base + a, base + b, base + c]);
+            } else {
+                self.indices
+                    .extend_from_slice(&[base + a, base + c, base + b]);
+            }
+        }
+        self.positions.extend(points);
+    }
+
+    fn ring(y: f32, radius: f32, segments: u32) -> impl Iterator<Item = Vec3> {
+        (0..segments).map(move |idx| {
+            let angle = idx as f32 / segments as f32 * TAU;
+            Vec3::new(angle.cos() * radius, y, angle.sin() * radius)
+        })
+    }
+
+    pub fn sphere(mut self, center: Vec3, radius: f32, rings: u32, segments: u32) -> Self {
+        let mut points = vec![center + Vec3::Y * radius];
+        for ring in 1..rings {
+            let theta = ring as f32 / rings as f32 * PI;
+            points.extend(
+                Self::ring(theta.cos() * radius, theta.sin() * radius, segments)
+                    .map(|p| center + p),
+            );
+        }
+        points.push(center - Vec3::Y * radius);
+
+        let bottom = points.len() as u32 - 1;
+        let ring_start = |ring: u32| 1 + (ring - 1) * segments;
+        let mut triangles = Vec::new();
+        for seg in 0..segments {
+            let next = (seg + 1) % segments;
+            triangles.push([0, ring_start(1) + seg, ring_start(1) + next]);
+            triangles.push([
+                bottom,
+                ring_start(rings - 1) + next,
+                ring_start(rings - 1) + seg,
+            ]);
+            for ring in 1..rings - 1 {
+                let (a, b) = (ring_start(ring), ring_start(ring + 1));
+                triangles.push([a + seg, b + seg, a + next]);
+                triangles.push([a + next, b + seg, b + next]);
+            }
+        }
+
+        self.convex_part(points, triangles);
+        self
+    }
+
+    /// Cone along the Y axis with its base circle at `base_y` and its tip at `apex_y`.
+    pub fn cone_y(mut self, base_y: f32, apex_y: f32, radius: f32, segments: u32) -> Self {
+        let mut points: Vec<_> = Self::ring(base_y, radius, segments).collect();
+        points.push(Vec3::new(0.0, apex_y, 0.0));
+        points.push(Vec3::new(0.0, base_y, 0.0));
+
+        let (apex, base_center) = (segments, segments + 1);
+        let triangles = (0..segments)
+            .flat_map(|seg| {
+                let next = (seg + 1) % segments;
+                [[apex, seg, next], [base_center, next, seg]]
+            })
+            .collect();
+
+        self.convex_part(points, triangles);
+        self
+    }
+
+    /// Capped cylinder along the Y axis spanning `bottom_y..top_y`.
+    pub fn cylinder_y(mut self, bottom_y: f32, top_y: f32, radius: f32, segments: u32) -> Self {
+        let mut points: Vec<_> = Self::ring(bottom_y, radius, segments)
+            .chain(Self::ring(top_y, radius, segments))
+            .collect();
+        points.push(Vec3::new(0.0, bottom_y, 0.0));
+        points.push(Vec3::new(0.0, top_y, 0.0));
+
+        let (bottom_center, top_center) = (segments * 2, segments * 2 + 1);
+        let triangles = (0..segments)
+            .flat_map(|seg| {
+                let next = (seg + 1) % segments;
+                [
+                    [seg, segments + seg, next],
+                    [next, segments + seg, segments + next],
+                    [bottom_center, seg, next],
+                    [top_center, segments + next, segments + seg],
+                ]
+            })
+            .collect();
+
+        self.convex_part(points, triangles);
+        self
+    }
+
+    /// Box with separate vertices per face, so it stays flat shaded.
+    pub fn cuboid(mut self, min: Vec3, max: Vec3) -> Self {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+
+        let mut points = Vec::with_capacity(24);
+        let mut triangles = Vec::with_capacity(12);
+        for axis in 0..3 {
+            for side in [false, true] {
+                let base = points.len() as u32;
+                for (u, v) in [(false, false), (true, false), (true, true), (false, true)] {
+                    points.push(match axis {
+                        0 => corner(side, u, v),
+                        1 => corner(u, side, v),
+                        _ => corner(u, v, side),
+                    });
+                }
+                triangles.push([base, base + 1, base + 2]);
+                triangles.push([base, base + 2, base + 3]);
+            }
+        }
+
+        self.convex_part(points, triangles);
+        self
+    }
+
+    pub fn build(self, handedness: Handedness) -> anyhow::Result<Mesh> {
+        Ok(MeshBuilder::new(self.positions, handedness)
+            .with_indices(self.indices)
+            .build()?)
+    }
+}
@@ -0,0 +1,170 @@
+use glam::{Vec3, Vec4};
+use image::{imageops, DynamicImage, Rgba32FImage, RgbaImage};
+
+/// Cube faces in the order rend3 expects them: +X, -X, +Y, -Y, +Z, -Z.
+pub const FACE_NAMES: [&str; 6] = ["right", "left", "top", "bottom", "front", "back"];
+
+/// File extensions tried for each face image, in order.
+pub const EXTENSIONS: [&str; 2] = ["jpg", "png"];
+
+fn face_index(name: &str) -> Result<usize, &'static str> {
+    FACE_NAMES
+        .iter()
+        .position(|&face| face == name.trim())
+        .ok_or("Skybox faces are named right, left, top, bottom, front and back")
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FaceFlip {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl FaceFlip {
+    pub fn apply(self, image: &mut RgbaImage) {
+        if self.horizontal {
+            imageops::flip_horizontal_in_place(image);
+        }
+        if self.vertical {
+            imageops::flip_vertical_in_place(image);
+        }
+    }
+}
+
+/// Direction through texel `(x, y)` of cube face `face` of a `size` sized cubemap, in the order
+/// of [`FACE_NAMES`].
+fn cube_direction(face: usize, x: u32, y: u32, size: u32) -> Vec3 {
+    let s = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+    let t = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+    match face {
+        0 => Vec3::new(1.0, -t, -s),
+        1 => Vec3::new(-1.0, -t, s),
+        2 => Vec3::new(s, 1.0, t),
+        3 => Vec3::new(s, -1.0, -t),
+        4 => Vec3::new(s, -t, 1.0),
+        _ => Vec3::new(-s, -t, -1.0),
+    }
+    .normalize()
+}
+
+/// Bilinearly filtered color of `image` at `u, v` from 0 to 1, wrapping around horizontally.
+fn sample_bilinear(image: &Rgba32FImage, u: f32, v: f32) -> Vec4 {
+    let (width, height) = image.dimensions();
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor(), y.floor());
+    let texel = |x: f32, y: f32| {
+        let x = (x as i64).rem_euclid(width as i64) as u32;
+        Vec4::from(image.get_pixel(x, (y as u32).min(height - 1)).0)
+    };
+    let top = texel(x0, y0).lerp(texel(x0 + 1.0, y0), x - x0);
+    let bottom = texel(x0, y0 + 1.0).lerp(texel(x0 + 1.0, y0 + 1.0), x - x0);
+    top.lerp(bottom, y - y0)
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Resamples an equirectangular panorama into the six `size` sized faces of a cubemap, in the
+/// order of [`FACE_NAMES`]. The middle of the panorama faces -Z and a quarter further to the
+/// right faces +X, like the --ibl environment.
+///
+/// 8 bit images are kept as they are encoded. Float images, like .hdr panoramas, hold linear
+/// values which are clamped to 1 and, with `srgb`, encoded to sRGB.
+pub fn equirect_to_cube(panorama: &DynamicImage, size: u32, srgb: bool) -> [RgbaImage; 6] {
+    let linear = matches!(
+        panorama,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    );
+    let panorama = panorama.to_rgba32f();
+    std::array::from_fn(|face| {
+        RgbaImage::from_fn(size, size, |x, y| {
+            let direction = cube_direction(face, x, y, size);
+            let longitude = direction.x.atan2(-direction.z);
+            let latitude = direction.y.clamp(-1.0, 1.0).asin();
+            let color = sample_bilinear(
+                &panorama,
+                longitude / std::f32::consts::TAU + 0.5,
+                0.5 - latitude / std::f32::consts::PI,
+            )
+            .clamp(Vec4::ZERO, Vec4::ONE);
+            let encode = |value: f32| {
+                let value = if linear && srgb {
+                    linear_to_srgb(value)
+                } else {
+                    value
+                };
+                (value * 255.0).round() as u8
+            };
+            image::Rgba([
+                encode(color.x),
+                encode(color.y),
+                encode(color.z),
+                (color.w * 255.0).round() as u8,
+            ])
+        })
+    })
+}
+
+/// Where the six skybox images are and how they map onto the cube, for cubemaps authored with a
+/// different convention.
+#[derive(Debug, Clone)]
+pub struct SkyboxLayout {
+    /// Directory with the images from --skybox, the bundled skybox/ resources when `None`.
+    pub directory: Option<String>,
+    /// Panorama from --skybox-equirect to make the faces out of instead of the images.
+    pub equirect: Option<String>,
+    /// Size of the faces made from `equirect`, a quarter of its width when `None`.
+    pub resolution: Option<u32>,
+    /// Index into [`FACE_NAMES`] of the image used for each cube face.
+    pub sources: [usize; 6],
+    /// Flips applied to each cube face after the images have been assigned.
+    pub flips: [FaceFlip; 6],
+    /// Whether the images are sRGB encoded rather than linear.
+    pub srgb: bool,
+}
+
+impl Default for SkyboxLayout {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            equirect: None,
+            resolution: None,
+            sources: [0, 1, 2, 3, 4, 5],
+            flips: [FaceFlip::default(); 6],
+            srgb: true,
+        }
+    }
+}
+
+/// Parses six comma separated face names, giving the image to use for each cube face in order.
+pub fn parse_sources(value: &str) -> Result<[usize; 6], &'static str> {
+    let sources: Vec<usize> = value.split(',').map(face_index).collect::<Result<_, _>>()?;
+    sources
+        .try_into()
+        .map_err(|_| "Skybox face order needs exactly six faces")
+}
+
+/// Parses `face:flip` pairs where flip is `h`, `v` or `hv`, like `top:h,bottom:hv`.
+pub fn parse_flips(value: &str) -> Result<[FaceFlip; 6], &'static str> {
+    let mut flips = [FaceFlip::default(); 6];
+    for entry in value.split(',') {
+        let (face, axes) = entry
+            .split_once(':')
+            .ok_or("Skybox flips are defined as face:h, face:v or face:hv")?;
+        let flip = &mut flips[face_index(face)?];
+        for axis in axes.trim().chars() {
+            match axis {
+                'h' => flip.horizontal = true,
+                'v' => flip.vertical = true,
+                _ => return Err("Skybox faces can only be flipped with h and v"),
+            }
+        }
+    }
+    Ok(flips)
+}
@@ -0,0 +1,76 @@
+use glam::Vec3;
+
+const DAYS_BEFORE_MONTH: [u32; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+/// February allows the 29th, which lands on the same day as March 1st.
+const DAYS_IN_MONTH: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Converts a month (1-12) and day of month into a day of the year, ignoring leap years.
+pub fn day_of_year(month: u32, day: u32) -> Option<u32> {
+    let index = month.checked_sub(1)? as usize;
+    let before = *DAYS_BEFORE_MONTH.get(index)?;
+    if day == 0 || day > DAYS_IN_MONTH[index] {
+        return None;
+    }
+    Some(before + day)
+}
+
+/// Simple solar position model, good to about a degree which is plenty for lighting.
+///
+/// The scene is assumed to have +Y up, -Z pointing north and +X pointing east.
+#[derive(Debug, Clone, Copy)]
+pub struct SunPosition {
+    /// Degrees, positive is north.
+    pub latitude: f32,
+    pub day_of_year: u32,
+    /// Local solar time in hours, 12.0 is solar noon.
+    pub time: f32,
+}
+
+impl SunPosition {
+    /// Elevation above the horizon and azimuth clockwise from north, both in radians.
+    pub fn elevation_azimuth(&self) -> (f32, f32) {
+        let latitude = self.latitude.to_radians();
+        let declination = -23.44_f32.to_radians()
+            * (std::f32::consts::TAU / 365.0 * (self.day_of_year as f32 + 10.0)).cos();
+        let hour_angle = (15.0 * (self.time - 12.0)).to_radians();
+
+        let sin_elevation = latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * hour_angle.cos();
+        let elevation = sin_elevation.clamp(-1.0, 1.0).asin();
+
+        let cos_azimuth = (declination.sin() - sin_elevation * latitude.sin())
+            / (elevation.cos() * latitude.cos()).max(f32::EPSILON);
+        let mut azimuth = cos_azimuth.clamp(-1.0, 1.0).acos();
+        // Afternoon sun is in the west.
+        if hour_angle > 0.0 {
+            azimuth = std::f32::consts::TAU - azimuth;
+        }
+
+        (elevation, azimuth)
+    }
+
+    /// Direction the sunlight travels in, suitable for a directional light.
+    pub fn light_direction(&self) -> Vec3 {
+        let (elevation, azimuth) = self.elevation_azimuth();
+        let to_sun = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            -elevation.cos() * azimuth.cos(),
+        );
+        -to_sun
+    }
+
+    /// Color of the sunlight, reddening towards the horizon, scaled by how much of it reaches
+    /// the scene: none a few degrees below the horizon, all of it ten degrees above.
+    pub fn daylight(&self) -> Vec3 {
+        let elevation = self.elevation_azimuth().0.to_degrees();
+        let strength = ((elevation + 4.0) / 14.0).clamp(0.0, 1.0);
+        let strength = strength * strength * (3.0 - 2.0 * strength);
+        let warmth = 1.0 - (elevation / 30.0).clamp(0.0, 1.0);
+        Vec3::ONE.lerp(Vec3::new(1.0, 0.45, 0.2), warmth) * strength
+    }
+
+    pub fn is_below_horizon(&self) -> bool {
+        self.elevation_azimuth().0 < 0.0
+    }
+}
@@ -0,0 +1,168 @@
+use gltf::json::Value;
+
+/// Which revision of the VRM extensions a file uses, they are laid out differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VrmVersion {
+    /// The `VRM` extension.
+    V0,
+    /// The `VRMC_vrm` extension.
+    V1,
+}
+
+/// Morph target weight an expression applies to one mesh.
+#[derive(Debug, Clone)]
+pub struct MorphBind {
+    /// Mesh index for VRM 0.x and node index for VRM 1.0, as the files store them.
+    pub target: usize,
+    pub morph_target: usize,
+    /// Weight from 0 to 1.
+    pub weight: f32,
+}
+
+/// A named facial expression (blendshape group in VRM 0.x).
+#[derive(Debug, Clone)]
+pub struct Expression {
+    pub name: String,
+    pub binds: Vec<MorphBind>,
+}
+
+/// What a VRM avatar adds on top of its gltf scene.
+#[derive(Debug, Clone)]
+pub struct VrmAvatar {
+    pub version: VrmVersion,
+    pub name: Option<String>,
+    /// Humanoid bone names like `hips` or `leftUpperArm` and the node they map to.
+    pub humanoid_bones: Vec<(String, usize)>,
+    pub expressions: Vec<Expression>,
+}
+
+/// Reads the VRM extensions of a gltf document, `None` if it isn't a VRM avatar.
+pub fn parse(document: &gltf::Document) -> Option<VrmAvatar> {
+    if let Some(vrm) = document.extension_value("VRMC_vrm") {
+        Some(parse_v1(vrm))
+    } else {
+        document.extension_value("VRM").map(parse_v0)
+    }
+}
+
+fn index(value: &Value) -> Option<usize> {
+    value.as_u64().map(|index| index as usize)
+}
+
+fn parse_v0(vrm: &Value) -> VrmAvatar {
+    let humanoid_bones = vrm["humanoid"]["humanBones"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|bone| Some((bone["bone"].as_str()?.to_owned(), index(&bone["node"])?)))
+        .collect();
+
+    let expressions = vrm["blendShapeMaster"]["blendShapeGroups"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|group| {
+            // Presets are the portable names, the display name is whatever the author typed.
+            let name = match group["presetName"].as_str() {
+                Some(preset) if preset != "unknown" => preset,
+                _ => group["name"].as_str()?,
+            };
+            let binds = group["binds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|bind| {
+                    Some(MorphBind {
+                        target: index(&bind["mesh"])?,
+                        morph_target: index(&bind["index"])?,
+                        // 0.x weights go up to 100.
+                        weight: bind["weight"].as_f64().unwrap_or(100.0) as f32 / 100.0,
+                    })
+                })
+                .collect();
+            Some(Expression {
+                name: name.to_owned(),
+                binds,
+            })
+        })
+        .collect();
+
+    VrmAvatar {
+        version: VrmVersion::V0,
+        name: vrm["meta"]["title"].as_str().map(str::to_owned),
+        humanoid_bones,
+        expressions,
+    }
+}
+
+fn parse_v1(vrm: &Value) -> VrmAvatar {
+    let humanoid_bones = vrm["humanoid"]["humanBones"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(bone, value)| Some((bone.clone(), index(&value["node"])?)))
+        .collect();
+
+    let expressions = ["preset", "custom"]
+        .iter()
+        .flat_map(|kind| vrm["expressions"][kind].as_object().into_iter().flatten())
+        .map(|(name, expression)| Expression {
+            name: name.clone(),
+            binds: expression["morphTargetBinds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|bind| {
+                    Some(MorphBind {
+                        target: index(&bind["node"])?,
+                        morph_target: index(&bind["index"])?,
+                        weight: bind["weight"].as_f64().unwrap_or(1.0) as f32,
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    VrmAvatar {
+        version: VrmVersion::V1,
+        name: vrm["meta"]["name"].as_str().map(str::to_owned),
+        humanoid_bones,
+        expressions,
+    }
+}
+
+impl VrmAvatar {
+    /// Logs the avatar, with every bone and expression at debug level.
+    pub fn log(&self) {
+        log::info!(
+            "VRM {} avatar {} with {} humanoid bones and {} expressions",
+            match self.version {
+                VrmVersion::V0 => "0.x",
+                VrmVersion::V1 => "1.0",
+            },
+            self.name.as_deref().unwrap_or("<unnamed>"),
+            self.humanoid_bones.len(),
+            self.expressions.len()
+        );
+        for (bone, node) in &self.humanoid_bones {
+            log::debug!("VRM bone {} is node {}", bone, node);
+        }
+        for expression in &self.expressions {
+            let target = match self.version {
+                VrmVersion::V0 => "mesh",
+                VrmVersion::V1 => "node",
+            };
+            let binds: Vec<String> = expression
+                .binds
+                .iter()
+                .map(|bind| {
+                    format!(
+                        "{} {} morph {} at {:.2}",
+                        target, bind.target, bind.morph_target, bind.weight
+                    )
+                })
+                .collect();
+            log::debug!("VRM expression {}: {}", expression.name, binds.join(", "));
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use std::borrow::Cow;
+
+use glam::{Mat4, UVec2, Vec3, Vec4};
+use rustc_hash::FxHashSet;
+use wgpu::util::DeviceExt;
+
+use crate::debug_lines;
+
+/// Fills the depth buffer the edges are tested against. Uses the start of the line params.
+const DEPTH_SHADER: &str = "
+struct Params {
+    view_proj: mat4x4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return params.view_proj * vec4<f32>(position, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+";
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+pub const DEFAULT_COLOR: Vec4 = Vec4::new(0.05, 0.05, 0.05, 0.8);
+
+/// Collects the triangles of every mesh in the default scene in their rest pose, as world space
+/// positions and a triangle list of indices into them. `buffers` holds the data of each gltf
+/// buffer, the scene is loaded with `scale` and placed with `transform`.
+pub fn collect_triangles(
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    scale: f32,
+    transform: Mat4,
+) -> (Vec<Vec3>, Vec<u32>) {
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    crate::scene_info::visit_nodes(document, scale, |node, node_transform| {
+        let transform = transform * node_transform;
+        let Some(mesh) = node.mesh() else {
+            return;
+        };
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+            let Some(primitive_positions) = reader.read_positions() else {
+                continue;
+            };
+            let base = positions.len() as u32;
+            positions.extend(
+                primitive_positions.map(|p| transform.transform_point3(Vec3::from_array(p))),
+            );
+            match reader.read_indices() {
+                Some(primitive_indices) => {
+                    indices.extend(primitive_indices.into_u32().map(|idx| base + idx))
+                }
+                None => indices.extend(base..positions.len() as u32),
+            }
+        }
+    });
+    (positions, indices)
+}
+
+/// Draws the edges of the scene's triangles over the shaded frame.
+///
+/// The triangles are first drawn into a depth buffer of their own, pushed back a little,
+/// so that only edges the shaded model doesn't cover show up. Edges are drawn as quads with
+/// the [`debug_lines`] shader, so they can be wider than the single pixel lines gpus draw.
+pub struct Wireframe {
+    depth_pipeline: wgpu::RenderPipeline,
+    line_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// Vertices, indices and index count of the triangles for the depth pass.
+    meshes: Vec<(wgpu::Buffer, wgpu::Buffer, u32)>,
+    /// Segment buffer of the edges and how many there are, see [`debug_lines::segment_bytes`].
+    edges: Vec<(wgpu::Buffer, u32)>,
+    depth: Option<(UVec2, wgpu::TextureView)>,
+    color: Vec4,
+    /// In pixels.
+    width: f32,
+}
+
+impl Wireframe {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        color: Vec4,
+        width: f32,
+    ) -> Self {
+        let depth_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("wireframe depth shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(DEPTH_SHADER)),
+        });
+        let line_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("wireframe line shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(debug_lines::SHADER)),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("wireframe bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("wireframe pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label,
+                        module: &wgpu::ShaderModule,
+                        buffer: wgpu::VertexBufferLayout,
+                        depth_write_enabled,
+                        bias,
+                        write_mask| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module,
+                    entry_point: "vs_main",
+                    buffers: &[buffer],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                // rend3 uses reverse z, so nearer is greater.
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled,
+                    depth_compare: wgpu::CompareFunction::GreaterEqual,
+                    stencil: wgpu::StencilState::default(),
+                    bias,
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+        let depth_pipeline = pipeline(
+            "wireframe depth pipeline",
+            &depth_module,
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vec3>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+            },
+            true,
+            wgpu::DepthBiasState {
+                constant: -4,
+                slope_scale: -1.0,
+                clamp: 0.0,
+            },
+            wgpu::ColorWrites::empty(),
+        );
+        let line_pipeline = pipeline(
+            "wireframe line pipeline",
+            &line_module,
+            debug_lines::segment_buffer_layout(),
+            false,
+            wgpu::DepthBiasState::default(),
+            wgpu::ColorWrites::ALL,
+        );
+
+        Self {
+            depth_pipeline,
+            line_pipeline,
+            bind_group_layout,
+            meshes: Vec::new(),
+            edges: Vec::new(),
+            depth: None,
+            color,
+            width,
+        }
+    }
+
+    /// Removes every mesh added so far.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+        self.edges.clear();
+    }
+
+    /// Adds triangles to draw, see [`collect_triangles`].
+    pub fn add_mesh(&mut self, device: &wgpu::Device, positions: &[Vec3], indices: &[u32]) {
+        if indices.is_empty() {
+            return;
+        }
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("wireframe vertices"),
+            contents: &positions
+                .iter()
+                .flat_map(|p| p.to_array())
+                .flat_map(f32::to_ne_bytes)
+                .collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("wireframe indices"),
+            contents: &indices
+                .iter()
+                .flat_map(|idx| idx.to_ne_bytes())
+                .collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        self.meshes
+            .push((vertex_buffer, index_buffer, indices.len() as u32));
+
+        // Triangles next to each other share edges, which would be drawn twice.
+        let mut edges = FxHashSet::default();
+        for triangle in indices.chunks_exact(3) {
+            for (a, b) in [
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        let edge_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("wireframe edges"),
+            contents: &debug_lines::segment_bytes(
+                edges
+                    .iter()
+                    .map(|&(a, b)| (positions[a as usize], positions[b as usize], self.color)),
+            ),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        self.edges.push((edge_buffer, edges.len() as u32));
+    }
+
+    /// Draws the wireframe into the `viewport` part of `target`, which is `resolution` big.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        resolution: UVec2,
+        viewport: rend3::graph::ViewportRect,
+        view_proj: Mat4,
+    ) {
+        profiling::scope!("wireframe");
+
+        if self.meshes.is_empty() {
+            return;
+        }
+        if self.depth.as_ref().map(|(size, _)| *size) != Some(resolution) {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("wireframe depth"),
+                size: wgpu::Extent3d {
+                    width: resolution.x,
+                    height: resolution.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.depth = Some((resolution, view));
+        }
+        let (_, ref depth) = self.depth.as_ref().unwrap();
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("wireframe params"),
+            contents: &debug_lines::params_bytes(view_proj, viewport.size, self.width),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("wireframe bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: params.as_entire_binding(),
+            }],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("wireframe encoder"),
+        });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("wireframe pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(0.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_viewport(
+                viewport.offset.x as f32,
+                viewport.offset.y as f32,
+                viewport.size.x as f32,
+                viewport.size.y as f32,
+                0.0,
+                1.0,
+            );
+            rpass.set_bind_group(0, &bind_group, &[]);
+            // All depth first, so that edges are hidden by every mesh and not just their own.
+            rpass.set_pipeline(&self.depth_pipeline);
+            for (vertex_buffer, index_buffer, index_count) in &self.meshes {
+                rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                rpass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                rpass.draw_indexed(0..*index_count, 0, 0..1);
+            }
+            rpass.set_pipeline(&self.line_pipeline);
+            for (edge_buffer, edge_count) in &self.edges {
+                rpass.set_vertex_buffer(0, edge_buffer.slice(..));
+                rpass.draw(0..6, 0..*edge_count);
+            }
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
@@ -0,0 +1,70 @@
+//! Renders a fixed scene offscreen and compares it against a committed golden image.
+//!
+//! Machines without any GPU adapter, like most CI runners, skip it with a message. Run with
+//! `UPDATE_GOLDEN=1` to write the golden image from the current output instead, after checking
+//! that a change to the rendering is intended.
+
+use std::path::Path;
+
+use glam::UVec2;
+use rend3_scene_viewer_example::headless;
+
+const GOLDEN_PATH: &str = "tests/golden/benchmark_grid.png";
+const SIZE: UVec2 = UVec2::new(320, 240);
+/// How far a channel may be off before the pixel counts as different, drivers don't all round
+/// the same way.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Share of pixels that may differ, for edges rasterized slightly differently.
+const PIXEL_TOLERANCE: f64 = 0.01;
+
+/// Whether wgpu finds anything to render with, software adapters included.
+fn has_adapter() -> bool {
+    wgpu::Instance::default()
+        .enumerate_adapters(wgpu::Backends::all())
+        .next()
+        .is_some()
+}
+
+#[test]
+fn benchmark_grid_matches_golden() {
+    if !has_adapter() {
+        eprintln!("Skipping the golden image test, there is no GPU adapter to render with");
+        return;
+    }
+    let frame = headless::render_benchmark_frame(SIZE).unwrap();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_PATH);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        frame.save(&path).unwrap();
+        eprintln!("Wrote golden image {}", path.display());
+        return;
+    }
+    assert!(
+        path.exists(),
+        "{} is missing, write it with UPDATE_GOLDEN=1 and commit it",
+        path.display()
+    );
+
+    let golden = image::open(&path).unwrap().to_rgba8();
+    assert_eq!(golden.dimensions(), frame.dimensions());
+    let different = golden
+        .pixels()
+        .zip(frame.pixels())
+        .filter(|(a, b)| {
+            a.0.iter()
+                .zip(b.0.iter())
+                .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+        })
+        .count();
+    let share = different as f64 / (SIZE.x * SIZE.y) as f64;
+    if share > PIXEL_TOLERANCE {
+        let actual = path.with_file_name("benchmark_grid.actual.png");
+        frame.save(&actual).unwrap();
+        panic!(
+            "{:.2}% of pixels differ from {}, the frame was written to {}",
+            share * 100.0,
+            path.display(),
+            actual.display()
+        );
+    }
+}